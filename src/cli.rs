@@ -1,3 +1,6 @@
+use crate::completions::CompletionKind;
+use crate::config::SyncStrategy;
+use crate::shell::Shell;
 use clap::{Parser, Subcommand};
 
 const DEFAULT_CONFIG_FILE: &str = ".gitws.yml";
@@ -16,6 +19,9 @@ pub enum Commands {
         task_name: String,
         #[arg(short, long, default_value = DEFAULT_CONFIG_FILE)]
         config: String,
+        /// Change into the new workspace (requires the shell integration, see shell-init)
+        #[arg(long)]
+        cd: bool,
     },
     List {
         #[arg(short, long, default_value = DEFAULT_CONFIG_FILE)]
@@ -26,6 +32,114 @@ pub enum Commands {
             help = "Print selected workspace path only"
         )]
         print_path_only: bool,
+        /// Print workspaces with ahead/behind information as JSON
+        #[arg(long, conflicts_with = "print_path_only")]
+        json: bool,
+        /// End printed paths with NUL instead of a newline
+        #[arg(long, conflicts_with = "json")]
+        print0: bool,
+        /// Write the output to a file instead of stdout (left empty when nothing is selected)
+        #[arg(long, value_name = "FILE")]
+        output_file: Option<String>,
+    },
+    /// Change into a workspace, picking from the matches when the query is ambiguous
+    Cd {
+        /// Workspace name, branch or fuzzy query, or `-` for the previously visited workspace
+        workspace: String,
+        #[arg(short, long, default_value = DEFAULT_CONFIG_FILE)]
+        config: String,
+    },
+    /// Run a command inside a workspace
+    Exec {
+        /// Workspace name, branch or fuzzy query
+        workspace: String,
+        #[arg(short, long, default_value = DEFAULT_CONFIG_FILE)]
+        config: String,
+        /// Command to run (a single argument is run through the shell)
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Run a command in every workspace
+    Foreach {
+        #[arg(short, long, default_value = DEFAULT_CONFIG_FILE)]
+        config: String,
+        /// Only run in workspaces whose task name or branch fuzzy-matches this pattern
+        #[arg(short, long)]
+        filter: Option<String>,
+        /// Number of workspaces to run in parallel
+        #[arg(short = 'j', long, default_value_t = 1)]
+        parallel: usize,
+        /// Print each workspace's output as one block instead of interleaved lines
+        #[arg(short, long)]
+        group: bool,
+        /// Command to run (a single argument is run through the shell)
+        #[arg(last = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Rebase or merge workspaces onto their base branch
+    Sync {
+        /// Workspace name, branch or fuzzy query
+        #[arg(required_unless_present = "all")]
+        workspace: Option<String>,
+        /// Sync every workspace
+        #[arg(short, long, conflicts_with = "workspace")]
+        all: bool,
+        /// Override the sync_strategy from the configuration
+        #[arg(short, long, value_enum)]
+        strategy: Option<SyncStrategy>,
+        /// Don't fetch remotes before syncing
+        #[arg(long)]
+        no_fetch: bool,
+        #[arg(short, long, default_value = DEFAULT_CONFIG_FILE)]
+        config: String,
+    },
+    /// Rename a workspace: its branch, directory and stored metadata
+    Rename {
+        /// Workspace name, branch or fuzzy query
+        workspace: String,
+        /// New task name (the timestamp prefix is kept)
+        new_name: String,
+    },
+    /// Lock a workspace so that gc and the TUI don't remove it, e.g. while an agent works in it
+    Lock {
+        /// Workspace name, branch or fuzzy query
+        workspace: String,
+        /// Why the workspace is locked, shown to whoever tries to remove it
+        #[arg(short, long)]
+        reason: Option<String>,
+    },
+    /// Unlock a workspace locked with `gitws lock`
+    Unlock {
+        /// Workspace name, branch or fuzzy query
+        workspace: String,
+    },
+    /// Remove workspaces merged into their base branch, or older or idler than the gc thresholds
+    Gc {
+        /// Remove without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Only show what would be removed
+        #[arg(short = 'n', long, conflicts_with = "yes")]
+        dry_run: bool,
+        /// Also remove locked workspaces
+        #[arg(short, long)]
+        force: bool,
+        /// Override gc.max_age_days from the configuration
+        #[arg(long, value_name = "DAYS")]
+        max_age: Option<u64>,
+        /// Override gc.max_idle_days from the configuration
+        #[arg(long, value_name = "DAYS")]
+        max_idle: Option<u64>,
+        #[arg(short, long, default_value = DEFAULT_CONFIG_FILE)]
+        config: String,
+    },
+    /// Find stale worktree registrations, orphan branches and stray directories, and fix them
+    Doctor {
+        /// Apply the fixes without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        #[arg(short, long, default_value = DEFAULT_CONFIG_FILE)]
+        config: String,
     },
     Init {
         #[arg(
@@ -36,6 +150,18 @@ pub enum Commands {
         )]
         output: String,
     },
+    /// Print shell code that lets gitws change the directory, for eval in your shell's rc file
+    ShellInit {
+        shell: Shell,
+        /// Don't bind Alt-W to picking a workspace in the TUI
+        #[arg(long)]
+        no_key_bindings: bool,
+    },
+    /// Print a completion script for your shell, generated from these commands
+    Completions { shell: clap_complete::Shell },
+    /// Print completion candidates for the completion scripts, one per line
+    #[command(name = "__complete", hide = true)]
+    Complete { kind: CompletionKind },
 }
 
 #[cfg(test)]
@@ -50,14 +176,30 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Commands::Start { task_name, config } => {
+            Commands::Start {
+                task_name,
+                config,
+                cd,
+            } => {
                 assert_eq!(task_name, "test-task");
                 assert_eq!(config, DEFAULT_CONFIG_FILE); // Default value
+                assert!(!cd);
             }
             _ => panic!("Expected Start command"),
         }
     }
 
+    #[test]
+    fn test_cli_start_command_with_cd() {
+        let args = vec!["ai-workspace", "start", "test-task", "--cd"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Start { cd, .. } => assert!(cd),
+            _ => panic!("Expected Start command"),
+        }
+    }
+
     #[test]
     fn test_cli_start_command_with_config() {
         // Specify config argument for start command
@@ -71,7 +213,9 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Commands::Start { task_name, config } => {
+            Commands::Start {
+                task_name, config, ..
+            } => {
                 assert_eq!(task_name, "test-task");
                 assert_eq!(config, "custom.yml");
             }
@@ -86,7 +230,9 @@ mod tests {
         let cli = Cli::try_parse_from(args).unwrap();
 
         match cli.command {
-            Commands::Start { task_name, config } => {
+            Commands::Start {
+                task_name, config, ..
+            } => {
                 assert_eq!(task_name, "test-task");
                 assert_eq!(config, "short.yml");
             }
@@ -104,6 +250,7 @@ mod tests {
             Commands::List {
                 config,
                 print_path_only,
+                ..
             } => {
                 assert_eq!(config, DEFAULT_CONFIG_FILE); // Default value
                 assert!(!print_path_only); // Default is false
@@ -122,6 +269,7 @@ mod tests {
             Commands::List {
                 config,
                 print_path_only,
+                ..
             } => {
                 assert_eq!(config, "custom.yml");
                 assert!(!print_path_only); // Default is false
@@ -140,6 +288,7 @@ mod tests {
             Commands::List {
                 config,
                 print_path_only,
+                ..
             } => {
                 assert_eq!(config, DEFAULT_CONFIG_FILE);
                 assert!(print_path_only);
@@ -158,6 +307,7 @@ mod tests {
             Commands::List {
                 config,
                 print_path_only,
+                ..
             } => {
                 assert_eq!(config, DEFAULT_CONFIG_FILE);
                 assert!(print_path_only);
@@ -182,6 +332,7 @@ mod tests {
             Commands::List {
                 config,
                 print_path_only,
+                ..
             } => {
                 assert_eq!(config, "test.yml");
                 assert!(print_path_only);
@@ -200,6 +351,7 @@ mod tests {
             Commands::List {
                 config,
                 print_path_only,
+                ..
             } => {
                 assert_eq!(config, "test.yml");
                 assert!(print_path_only);
@@ -208,6 +360,85 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_cli_list_command_with_json() {
+        let args = vec!["ai-workspace", "list", "--json"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::List {
+                print_path_only,
+                json,
+                ..
+            } => {
+                assert!(!print_path_only);
+                assert!(json);
+            }
+            _ => panic!("Expected List command"),
+        }
+
+        // --json and --path-only are exclusive
+        let args = vec!["ai-workspace", "list", "--json", "--path-only"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_cli_list_command_with_output_options() {
+        let args = vec![
+            "ai-workspace",
+            "list",
+            "--print0",
+            "--output-file",
+            "/tmp/gitws-selection",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::List {
+                print0,
+                output_file,
+                ..
+            } => {
+                assert!(print0);
+                assert_eq!(output_file.as_deref(), Some("/tmp/gitws-selection"));
+            }
+            _ => panic!("Expected List command"),
+        }
+
+        // NUL separators only apply to paths
+        let args = vec!["ai-workspace", "list", "--json", "--print0"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn test_cli_shell_init_command() {
+        let args = vec!["ai-workspace", "shell-init", "zsh"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::ShellInit {
+                shell,
+                no_key_bindings,
+            } => {
+                assert_eq!(shell, Shell::Zsh);
+                assert!(!no_key_bindings);
+            }
+            _ => panic!("Expected ShellInit command"),
+        }
+
+        let args = vec!["ai-workspace", "shell-init", "fish", "--no-key-bindings"];
+        assert!(matches!(
+            Cli::try_parse_from(args).unwrap().command,
+            Commands::ShellInit {
+                shell: Shell::Fish,
+                no_key_bindings: true,
+            }
+        ));
+
+        let args = vec!["ai-workspace", "shell-init", "tcsh"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
     #[test]
     fn test_cli_invalid_command() {
         // Verify that invalid command causes error
@@ -291,4 +522,276 @@ mod tests {
             _ => panic!("Expected Init command"),
         }
     }
+
+    #[test]
+    fn test_cli_exec_command() {
+        let args = vec![
+            "ai-workspace",
+            "exec",
+            "feature-auth",
+            "--",
+            "cargo",
+            "test",
+            "--all",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Exec {
+                workspace,
+                config,
+                command,
+            } => {
+                assert_eq!(workspace, "feature-auth");
+                assert_eq!(config, DEFAULT_CONFIG_FILE);
+                assert_eq!(command, vec!["cargo", "test", "--all"]);
+            }
+            _ => panic!("Expected Exec command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_exec_command_without_command() {
+        // Verify that exec command without a command causes error
+        let args = vec!["ai-workspace", "exec", "feature-auth"];
+        let result = Cli::try_parse_from(args);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_cli_foreach_command() {
+        let args = vec!["ai-workspace", "foreach", "--", "cargo test"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Foreach {
+                config,
+                filter,
+                parallel,
+                group,
+                command,
+            } => {
+                assert_eq!(config, DEFAULT_CONFIG_FILE);
+                assert!(filter.is_none());
+                assert_eq!(parallel, 1); // Default value
+                assert!(!group);
+                assert_eq!(command, vec!["cargo test"]);
+            }
+            _ => panic!("Expected Foreach command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_foreach_command_with_options() {
+        let args = vec![
+            "ai-workspace",
+            "foreach",
+            "--filter",
+            "feature",
+            "--parallel",
+            "4",
+            "--group",
+            "--",
+            "git",
+            "fetch",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Foreach {
+                filter,
+                parallel,
+                group,
+                command,
+                ..
+            } => {
+                assert_eq!(filter.as_deref(), Some("feature"));
+                assert_eq!(parallel, 4);
+                assert!(group);
+                assert_eq!(command, vec!["git", "fetch"]);
+            }
+            _ => panic!("Expected Foreach command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_sync_command() {
+        let args = vec!["ai-workspace", "sync", "feature-auth"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Sync {
+                workspace,
+                all,
+                strategy,
+                no_fetch,
+                config,
+            } => {
+                assert_eq!(workspace.as_deref(), Some("feature-auth"));
+                assert!(!all);
+                assert!(strategy.is_none());
+                assert!(!no_fetch);
+                assert_eq!(config, DEFAULT_CONFIG_FILE);
+            }
+            _ => panic!("Expected Sync command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_sync_command_all_with_strategy() {
+        let args = vec!["ai-workspace", "sync", "--all", "--strategy", "merge"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Sync {
+                workspace,
+                all,
+                strategy,
+                ..
+            } => {
+                assert!(workspace.is_none());
+                assert!(all);
+                assert_eq!(strategy, Some(SyncStrategy::Merge));
+            }
+            _ => panic!("Expected Sync command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_rename_command() {
+        let args = vec!["ai-workspace", "rename", "feature-auth", "login-flow"];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        match cli.command {
+            Commands::Rename {
+                workspace,
+                new_name,
+            } => {
+                assert_eq!(workspace, "feature-auth");
+                assert_eq!(new_name, "login-flow");
+            }
+            _ => panic!("Expected Rename command"),
+        }
+
+        // Both the workspace and the new name are required
+        assert!(Cli::try_parse_from(vec!["ai-workspace", "rename", "feature-auth"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_sync_command_requires_target() {
+        // Either a workspace or --all is required, but not both
+        assert!(Cli::try_parse_from(vec!["ai-workspace", "sync"]).is_err());
+        assert!(Cli::try_parse_from(vec!["ai-workspace", "sync", "ws", "--all"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_cd_command() {
+        let cli = Cli::try_parse_from(vec!["ai-workspace", "cd", "auth"]).unwrap();
+        match cli.command {
+            Commands::Cd { workspace, config } => {
+                assert_eq!(workspace, "auth");
+                assert_eq!(config, DEFAULT_CONFIG_FILE);
+            }
+            _ => panic!("Expected Cd command"),
+        }
+
+        // `-` is the previously visited workspace, not an option
+        let cli = Cli::try_parse_from(vec!["ai-workspace", "cd", "-"]).unwrap();
+        match cli.command {
+            Commands::Cd { workspace, .. } => assert_eq!(workspace, "-"),
+            _ => panic!("Expected Cd command"),
+        }
+        assert!(Cli::try_parse_from(vec!["ai-workspace", "cd"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_gc_command() {
+        let cli = Cli::try_parse_from(vec!["ai-workspace", "gc"]).unwrap();
+        match cli.command {
+            Commands::Gc {
+                yes,
+                dry_run,
+                force,
+                max_age,
+                max_idle,
+                ..
+            } => {
+                assert!(!yes);
+                assert!(!dry_run);
+                assert!(!force);
+                assert_eq!(max_age, None);
+                assert_eq!(max_idle, None);
+            }
+            _ => panic!("Expected Gc command"),
+        }
+
+        let cli =
+            Cli::try_parse_from(vec!["ai-workspace", "gc", "-y", "--max-idle", "14"]).unwrap();
+        match cli.command {
+            Commands::Gc { yes, max_idle, .. } => {
+                assert!(yes);
+                assert_eq!(max_idle, Some(14));
+            }
+            _ => panic!("Expected Gc command"),
+        }
+        assert!(Cli::try_parse_from(vec!["ai-workspace", "gc", "--yes", "--dry-run"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_lock_commands() {
+        let cli = Cli::try_parse_from(vec![
+            "ai-workspace",
+            "lock",
+            "auth",
+            "--reason",
+            "agent running",
+        ])
+        .unwrap();
+        match cli.command {
+            Commands::Lock { workspace, reason } => {
+                assert_eq!(workspace, "auth");
+                assert_eq!(reason.as_deref(), Some("agent running"));
+            }
+            _ => panic!("Expected Lock command"),
+        }
+        let cli = Cli::try_parse_from(vec!["ai-workspace", "lock", "auth"]).unwrap();
+        assert!(matches!(cli.command, Commands::Lock { reason: None, .. }));
+
+        let cli = Cli::try_parse_from(vec!["ai-workspace", "unlock", "auth"]).unwrap();
+        match cli.command {
+            Commands::Unlock { workspace } => assert_eq!(workspace, "auth"),
+            _ => panic!("Expected Unlock command"),
+        }
+        assert!(Cli::try_parse_from(vec!["ai-workspace", "unlock"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_doctor_command() {
+        let cli = Cli::try_parse_from(vec!["ai-workspace", "doctor"]).unwrap();
+        match cli.command {
+            Commands::Doctor { yes, config } => {
+                assert!(!yes);
+                assert_eq!(config, DEFAULT_CONFIG_FILE);
+            }
+            _ => panic!("Expected Doctor command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["ai-workspace", "doctor", "-y"]).unwrap();
+        assert!(matches!(cli.command, Commands::Doctor { yes: true, .. }));
+    }
+
+    #[test]
+    fn test_cli_completions_command() {
+        let cli = Cli::try_parse_from(vec!["ai-workspace", "completions", "zsh"]).unwrap();
+        match cli.command {
+            Commands::Completions { shell } => assert_eq!(shell, clap_complete::Shell::Zsh),
+            _ => panic!("Expected Completions command"),
+        }
+
+        let cli = Cli::try_parse_from(vec!["ai-workspace", "__complete", "branches"]).unwrap();
+        match cli.command {
+            Commands::Complete { kind } => assert_eq!(kind, CompletionKind::Branches),
+            _ => panic!("Expected Complete command"),
+        }
+    }
 }
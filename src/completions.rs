@@ -0,0 +1,242 @@
+use crate::cli::Cli;
+use crate::workspace::WorkspaceInfo;
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+/// Id of the positional argument that names a workspace; these get dynamic completion
+const WORKSPACE_ARG: &str = "workspace";
+
+/// Candidates printed by the hidden `gitws __complete` subcommand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompletionKind {
+    /// Workspace (worktree) names
+    Workspaces,
+    /// Branches checked out in the workspaces
+    Branches,
+}
+
+/// Candidates of the given kind, one per line
+pub fn candidates(workspaces: &[WorkspaceInfo], kind: CompletionKind) -> String {
+    let mut output = String::new();
+    for workspace in workspaces {
+        output.push_str(match kind {
+            CompletionKind::Workspaces => &workspace.name,
+            CompletionKind::Branches => &workspace.branch,
+        });
+        output.push('\n');
+    }
+    output
+}
+
+/// Completion script generated from the command definitions. For bash, zsh and fish the
+/// workspace arguments are completed by calling `gitws __complete` when the user presses Tab.
+pub fn completion_script(shell: Shell) -> String {
+    // Hidden subcommands are left out: the generators would offer `__complete` as a command
+    let cli = Cli::command();
+    let mut command = clap::Command::new("gitws")
+        .about(cli.get_about().cloned().unwrap_or_default())
+        .subcommands(
+            cli.get_subcommands()
+                .filter(|sc| !sc.is_hide_set())
+                .cloned(),
+        );
+    let mut buffer = Vec::new();
+    clap_complete::generate(shell, &mut command, "gitws", &mut buffer);
+    let script = String::from_utf8_lossy(&buffer).into_owned();
+
+    let subcommands = workspace_subcommands(&command);
+    match shell {
+        Shell::Bash => script + &bash_workspaces(&subcommands, &value_options(&command)),
+        Shell::Zsh => zsh_workspaces(&script),
+        Shell::Fish => script + &fish_workspaces(&subcommands),
+        _ => script,
+    }
+}
+
+/// Subcommands taking a workspace as their first positional argument
+fn workspace_subcommands(command: &clap::Command) -> Vec<String> {
+    command
+        .get_subcommands()
+        .filter(|sc| {
+            sc.get_positionals()
+                .next()
+                .is_some_and(|arg| arg.get_id() == WORKSPACE_ARG)
+        })
+        .map(|sc| sc.get_name().to_string())
+        .collect()
+}
+
+/// Options of those subcommands that take a value, so the value isn't mistaken for the workspace
+fn value_options(command: &clap::Command) -> Vec<String> {
+    let subcommands = workspace_subcommands(command);
+    let mut options: Vec<String> = command
+        .get_subcommands()
+        .filter(|sc| subcommands.iter().any(|name| name == sc.get_name()))
+        .flat_map(|sc| sc.get_opts())
+        .filter(|arg| arg.get_action().takes_values())
+        .flat_map(|arg| {
+            let short = arg.get_short().map(|short| format!("-{short}"));
+            let long = arg.get_long().map(|long| format!("--{long}"));
+            short.into_iter().chain(long)
+        })
+        .collect();
+    options.sort();
+    options.dedup();
+    options
+}
+
+fn bash_workspaces(subcommands: &[String], value_options: &[String]) -> String {
+    format!(
+        r#"
+# Workspace names and branches, from `gitws __complete`
+_gitws_workspaces() {{
+    local i word positionals=0
+    _gitws "$@"
+    case "${{COMP_WORDS[1]}}" in
+        {subcommands}) ;;
+        *) return 0 ;;
+    esac
+    [[ ${{COMP_WORDS[COMP_CWORD]}} == -* ]] && return 0
+    for ((i = 2; i < COMP_CWORD; i++)); do
+        word="${{COMP_WORDS[i]}}"
+        case "$word" in
+            {value_options}) ((i++)) ;;
+            -*) ;;
+            *) ((positionals++)) ;;
+        esac
+    done
+    if [[ $positionals -eq 0 ]]; then
+        COMPREPLY=( $(compgen -W "$(command gitws __complete workspaces 2>/dev/null) $(command gitws __complete branches 2>/dev/null)" -- "${{COMP_WORDS[COMP_CWORD]}}") )
+    fi
+}}
+complete -F _gitws_workspaces -o bashdefault -o default gitws
+"#,
+        subcommands = subcommands.join("|"),
+        value_options = value_options.join("|"),
+    )
+}
+
+const ZSH_WORKSPACES: &str = r#"(( $+functions[_gitws_workspaces] )) ||
+_gitws_workspaces() {
+    local -a workspaces branches
+    workspaces=(${(f)"$(command gitws __complete workspaces 2>/dev/null)"})
+    branches=(${(f)"$(command gitws __complete branches 2>/dev/null)"})
+    _alternative \
+        'workspaces:workspace:compadd -a workspaces' \
+        'branches:branch:compadd -a branches'
+}
+
+"#;
+
+fn zsh_workspaces(script: &str) -> String {
+    // Point the workspace arguments at _gitws_workspaces and define it before the script's
+    // trailing block, which runs the completion when the file is autoloaded from $fpath
+    let mut output = String::new();
+    for line in script.split_inclusive('\n') {
+        if line.starts_with("if [ \"$funcstack[1]\" = \"_gitws\" ]") {
+            output.push_str(ZSH_WORKSPACES);
+        }
+        let is_workspace_arg = line
+            .trim_start_matches('\'')
+            .trim_start_matches(':')
+            .starts_with(&format!("{WORKSPACE_ARG} -- "));
+        match line.strip_suffix(":_default' \\\n") {
+            Some(spec) if is_workspace_arg => {
+                output.push_str(spec);
+                output.push_str(":_gitws_workspaces' \\\n");
+            }
+            _ => output.push_str(line),
+        }
+    }
+    output
+}
+
+fn fish_workspaces(subcommands: &[String]) -> String {
+    let mut output = String::from("\n# Workspace names and branches, from `gitws __complete`\n");
+    for subcommand in subcommands {
+        output.push_str(&format!(
+            "complete -c gitws -n \"__fish_gitws_using_subcommand {subcommand}; and __fish_is_nth_token 2\" -f -a \"(command gitws __complete workspaces 2>/dev/null) (command gitws __complete branches 2>/dev/null)\"\n"
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidates() {
+        let workspaces = vec![
+            WorkspaceInfo {
+                name: "20250101-120000-auth".to_string(),
+                path: "/tmp/ws/20250101-120000-auth".to_string(),
+                branch: "work/20250101-120000-auth".to_string(),
+                locked: None,
+            },
+            WorkspaceInfo {
+                name: "20250102-090000-docs".to_string(),
+                path: "/tmp/ws/20250102-090000-docs".to_string(),
+                branch: "docs-rewrite".to_string(),
+                locked: None,
+            },
+        ];
+        assert_eq!(
+            candidates(&workspaces, CompletionKind::Workspaces),
+            "20250101-120000-auth\n20250102-090000-docs\n"
+        );
+        assert_eq!(
+            candidates(&workspaces, CompletionKind::Branches),
+            "work/20250101-120000-auth\ndocs-rewrite\n"
+        );
+        assert_eq!(candidates(&[], CompletionKind::Workspaces), "");
+    }
+
+    #[test]
+    fn test_completion_script() {
+        let subcommands = workspace_subcommands(&Cli::command());
+        assert!(subcommands.contains(&"exec".to_string()));
+        assert!(subcommands.contains(&"sync".to_string()));
+        assert!(!subcommands.contains(&"start".to_string()));
+
+        let bash = completion_script(Shell::Bash);
+        assert!(bash.contains("_gitws()"));
+        assert!(bash.contains("complete -F _gitws_workspaces"));
+        assert!(bash.contains("--config|"));
+        assert!(!bash.contains("__complete)"));
+
+        let zsh = completion_script(Shell::Zsh);
+        assert!(
+            zsh.contains(":workspace -- Workspace name, branch or fuzzy query:_gitws_workspaces'")
+        );
+        assert!(
+            zsh.find("_gitws_workspaces() {").unwrap()
+                < zsh.find("if [ \"$funcstack[1]\" = \"_gitws\" ]").unwrap()
+        );
+
+        let fish = completion_script(Shell::Fish);
+        assert!(fish.contains("__fish_gitws_using_subcommand rename; and __fish_is_nth_token 2"));
+
+        // Shells without dynamic completion still get the generated script
+        assert!(completion_script(Shell::PowerShell).contains("gitws"));
+    }
+
+    #[test]
+    fn test_completion_script_syntax() {
+        let path = std::env::temp_dir().join("gitws-completions-bash");
+        std::fs::write(&path, completion_script(Shell::Bash)).unwrap();
+        match std::process::Command::new("bash")
+            .arg("-n")
+            .arg(&path)
+            .output()
+        {
+            Ok(output) => assert!(
+                output.status.success(),
+                "{}",
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Err(_) => eprintln!("bash not installed, skipping"),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,13 +1,26 @@
 use crate::error::{GitwsError, GitwsResult};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::{self, Write};
 use std::path::Path;
 use tracing::{debug, error, warn};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WorkspaceConfig {
     pub workspace: WorkspaceSettings,
+    #[serde(default)]
+    pub tui: TuiSettings,
+    #[serde(default)]
+    pub gc: GcSettings,
+}
+
+/// How `gitws sync` brings a workspace up to date with its base branch
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStrategy {
+    #[default]
+    Rebase,
+    Merge,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,6 +29,93 @@ pub struct WorkspaceSettings {
     pub branch_prefix: String,
     pub copy_files: Vec<String>,
     pub pre_commands: Vec<String>,
+    /// Worktree name or directory that copy_files are copied from (defaults to the current directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy_source: Option<String>,
+    /// Environment variables set for commands run inside workspaces (gitws exec)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Strategy used by gitws sync (rebase or merge)
+    #[serde(default)]
+    pub sync_strategy: SyncStrategy,
+}
+
+/// Thresholds of `gitws gc`; workspaces whose branch is merged into the base are always collected
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub struct GcSettings {
+    /// Collect workspaces created more than this many days ago
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u64>,
+    /// Collect workspaces without activity (commits, index or file changes) for this many days
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_idle_days: Option<u64>,
+}
+
+/// Settings of the `gitws list` TUI
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TuiSettings {
+    /// Commands launched for the highlighted workspace
+    #[serde(default)]
+    pub open: OpenSettings,
+    /// Keys of TUI actions by action name, overriding the defaults (e.g. `delete: "D"`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keymap: BTreeMap<String, KeyBindings>,
+    /// Built-in colour theme
+    #[serde(default)]
+    pub theme: ThemeName,
+    /// Colours overriding the theme by role (e.g. `accent: "#268bd2"`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// Built-in colour themes of the TUI
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+/// A single key or a list of alternative keys; chords are space separated ("g d")
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum KeyBindings {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBindings {
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyBindings::One(key) => vec![key.as_str()],
+            KeyBindings::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Open actions of the TUI (unset actions fall back to built-in defaults)
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct OpenSettings {
+    /// 'e': defaults to $VISUAL/$EDITOR, or VS Code when neither is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<OpenCommand>,
+    /// 't': defaults to a new tmux window when running inside tmux
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub terminal: Option<OpenCommand>,
+    /// 'o': no default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom: Option<OpenCommand>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct OpenCommand {
+    /// Shell command run in the workspace directory with the GITWS_* variables set
+    pub command: String,
+    /// Suspend the TUI until the command exits (for terminal programs such as vim)
+    #[serde(default)]
+    pub wait: bool,
 }
 
 impl Default for WorkspaceConfig {
@@ -26,7 +126,12 @@ impl Default for WorkspaceConfig {
                 branch_prefix: "work/".to_string(),
                 copy_files: vec![],
                 pre_commands: vec![],
+                copy_source: None,
+                env: BTreeMap::new(),
+                sync_strategy: SyncStrategy::Rebase,
             },
+            tui: TuiSettings::default(),
+            gc: GcSettings::default(),
         }
     }
 }
@@ -110,23 +215,12 @@ pub fn generate_template_config(output_path: &str) -> GitwsResult<()> {
         debug!("Configuration file already exists: {}", output_path);
 
         // Ask for confirmation to overwrite
-        print!(
-            "Configuration file '{}' already exists. Overwrite? (y/N): ",
-            output_path
-        );
-        io::stdout().flush().map_err(|e| {
-            error!("Failed to flush stdout: {}", e);
-            GitwsError::io(format!("IO error: {e}"))
-        })?;
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).map_err(|e| {
+        let prompt = format!("Configuration file '{output_path}' already exists. Overwrite?");
+        let overwrite = crate::utils::confirm(&prompt).map_err(|e| {
             error!("Failed to read user input: {}", e);
             GitwsError::io(format!("Input error: {e}"))
         })?;
-
-        let input = input.trim().to_lowercase();
-        if input != "y" && input != "yes" {
+        if !overwrite {
             debug!("User cancelled overwrite operation");
             println!("Operation cancelled.");
             return Ok(());
@@ -169,12 +263,55 @@ workspace:
     # - "config/database.yml"
     # - "docker-compose.override.yml"
   
+  # Worktree name or directory to copy files from (defaults to the current directory).
+  # Useful for bare repository layouts where there is no main working tree.
+  # copy_source: "main"
+  
+  # Environment variables for commands run with `gitws exec`
+  # env:
+  #   RUST_LOG: "debug"
+  
+  # How `gitws sync` updates workspaces from their base branch: rebase or merge
+  sync_strategy: rebase
+  
   # Commands to execute after workspace creation
   pre_commands:
     - "npm install"
     # - "cargo build"
     # - "bundle install"
     # - "docker-compose up -d"
+
+# TUI (`gitws list`) settings
+# tui:
+#   # Commands launched for the highlighted workspace, run in the workspace directory
+#   # with GITWS_WORKSPACE, GITWS_WORKSPACE_PATH, GITWS_BRANCH and GITWS_TASK set.
+#   # Set wait: true for terminal programs; the TUI is suspended until they exit.
+#   open:
+#     editor:              # 'e' (default: $VISUAL/$EDITOR, or `code .`)
+#       command: "code ."
+#     terminal:            # 't' (default: a new tmux window inside tmux)
+#       command: "tmux new-window -c \"$GITWS_WORKSPACE_PATH\" -n \"$GITWS_TASK\""
+#     custom:              # 'o'
+#       command: "lazygit"
+#       wait: true
+#   # Keys of TUI actions (press ? in the TUI for the full list of actions).
+#   # Modifiers: ctrl-, alt-, shift-; chords are space separated; [] unbinds an action.
+#   keymap:
+#     delete: "D"
+#     quit: ["q", "ctrl-c"]
+#     diff: "g d"
+#   # Colour theme: dark, light or high-contrast (colours are disabled when NO_COLOR is set).
+#   # Roles can be overridden with colour names, '#rrggbb' or 256-colour indices.
+#   theme: dark
+#   colors:
+#     accent: '#268bd2'
+#     selected: "yellow"
+
+# `gitws gc` settings: besides workspaces merged into their base branch, remove clean,
+# fully pushed workspaces older or idler than these thresholds
+# gc:
+#   max_age_days: 30
+#   max_idle_days: 14
 "#
     .to_string()
 }
@@ -191,6 +328,7 @@ mod tests {
         assert_eq!(config.workspace.branch_prefix, "work/");
         assert!(config.workspace.copy_files.is_empty());
         assert!(config.workspace.pre_commands.is_empty());
+        assert!(config.workspace.copy_source.is_none());
     }
 
     #[test]
@@ -220,6 +358,154 @@ workspace:
         assert_eq!(config.workspace.branch_prefix, "test/");
         assert_eq!(config.workspace.copy_files, vec![".env", ".env.local"]);
         assert_eq!(config.workspace.pre_commands, vec!["echo 'setup complete'"]);
+        assert!(config.workspace.copy_source.is_none());
+        assert_eq!(config.workspace.sync_strategy, SyncStrategy::Rebase);
+    }
+
+    #[test]
+    fn test_workspace_config_sync_strategy() {
+        let yaml = r#"
+workspace:
+  base_dir: "../workspaces"
+  branch_prefix: "work/"
+  copy_files: []
+  pre_commands: []
+  sync_strategy: merge
+"#;
+        let config: WorkspaceConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.workspace.sync_strategy, SyncStrategy::Merge);
+    }
+
+    #[test]
+    fn test_template_content_is_valid() {
+        let config: WorkspaceConfig = serde_yaml::from_str(&create_template_content()).unwrap();
+        assert_eq!(config.workspace.base_dir, "../workspaces");
+        assert_eq!(config.workspace.sync_strategy, SyncStrategy::Rebase);
+    }
+
+    #[test]
+    fn test_workspace_config_copy_source() {
+        let yaml = r#"
+workspace:
+  base_dir: "../worktrees"
+  branch_prefix: "work/"
+  copy_files:
+    - ".env"
+  pre_commands: []
+  copy_source: "main"
+"#;
+        let config: WorkspaceConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.workspace.copy_source.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_workspace_config_env() {
+        let yaml = r#"
+workspace:
+  base_dir: "../workspaces"
+  branch_prefix: "work/"
+  copy_files: []
+  pre_commands: []
+  env:
+    NODE_ENV: "development"
+    PORT: "3001"
+"#;
+        let config: WorkspaceConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.workspace.env.len(), 2);
+        assert_eq!(config.workspace.env["NODE_ENV"], "development");
+        assert_eq!(config.workspace.env["PORT"], "3001");
+    }
+
+    #[test]
+    fn test_workspace_config_tui_open() {
+        let yaml = r#"
+workspace:
+  base_dir: "../workspaces"
+  branch_prefix: "work/"
+  copy_files: []
+  pre_commands: []
+tui:
+  open:
+    editor:
+      command: "vim ."
+      wait: true
+    custom:
+      command: "open ."
+"#;
+        let config: WorkspaceConfig = serde_yaml::from_str(yaml).unwrap();
+        let open = &config.tui.open;
+        assert_eq!(
+            open.editor,
+            Some(OpenCommand {
+                command: "vim .".to_string(),
+                wait: true,
+            })
+        );
+        assert!(open.terminal.is_none());
+        assert_eq!(open.custom.as_ref().map(|c| c.wait), Some(false));
+
+        // The section is optional
+        assert_eq!(WorkspaceConfig::default().tui, TuiSettings::default());
+    }
+
+    #[test]
+    fn test_workspace_config_tui_keymap() {
+        let yaml = r#"
+workspace:
+  base_dir: "../workspaces"
+  branch_prefix: "work/"
+  copy_files: []
+  pre_commands: []
+tui:
+  keymap:
+    delete: "D"
+    quit: ["q", "ctrl-c"]
+    details: []
+"#;
+        let config: WorkspaceConfig = serde_yaml::from_str(yaml).unwrap();
+        let keymap = &config.tui.keymap;
+        assert_eq!(keymap["delete"].keys(), vec!["D"]);
+        assert_eq!(keymap["quit"].keys(), vec!["q", "ctrl-c"]);
+        assert!(keymap["details"].keys().is_empty());
+    }
+
+    #[test]
+    fn test_workspace_config_tui_theme() {
+        let yaml = r##"
+workspace:
+  base_dir: "../workspaces"
+  branch_prefix: "work/"
+  copy_files: []
+  pre_commands: []
+tui:
+  theme: high-contrast
+  colors:
+    accent: "#268bd2"
+"##;
+        let config: WorkspaceConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.tui.theme, ThemeName::HighContrast);
+        assert_eq!(config.tui.colors["accent"], "#268bd2");
+
+        assert_eq!(WorkspaceConfig::default().tui.theme, ThemeName::Dark);
+    }
+
+    #[test]
+    fn test_workspace_config_gc() {
+        let yaml = r#"
+workspace:
+  base_dir: "../workspaces"
+  branch_prefix: "work/"
+  copy_files: []
+  pre_commands: []
+gc:
+  max_idle_days: 14
+"#;
+        let config: WorkspaceConfig = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(config.gc.max_idle_days, Some(14));
+        assert_eq!(config.gc.max_age_days, None);
+
+        // Without thresholds only merged workspaces are collected
+        assert_eq!(WorkspaceConfig::default().gc, GcSettings::default());
     }
 
     #[test]
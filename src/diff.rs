@@ -0,0 +1,326 @@
+use git2::{Delta, DiffOptions, Patch, Repository};
+use std::path::Path;
+use tracing::debug;
+
+/// What the working tree of a workspace is compared with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffTarget {
+    /// Merge base with the base branch: everything done in the workspace
+    Base,
+    /// HEAD: uncommitted changes only
+    Head,
+}
+
+impl DiffTarget {
+    pub fn toggle(self) -> Self {
+        match self {
+            DiffTarget::Base => DiffTarget::Head,
+            DiffTarget::Head => DiffTarget::Base,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    /// "---"/"+++" lines and the binary file marker
+    FileHeader,
+    /// "@@ -1,3 +1,4 @@"
+    HunkHeader,
+    Context,
+    Addition,
+    Deletion,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+}
+
+/// Unified diff of one file
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    pub path: String,
+    /// One-letter status as in `git diff --name-status` (A, M, D, R, T)
+    pub status: char,
+    pub additions: usize,
+    pub deletions: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Changes of a workspace against a diff target
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkspaceDiff {
+    /// Ref or commit the working tree was compared with
+    pub compared_with: String,
+    pub files: Vec<FileDiff>,
+}
+
+/// Diff the working tree (including staged and untracked files) of a workspace
+/// against `base` (a ref whose merge base with HEAD is used) or HEAD
+pub fn workspace_diff(
+    workspace_path: &Path,
+    base: Option<&str>,
+    target: DiffTarget,
+) -> Result<WorkspaceDiff, git2::Error> {
+    debug!(
+        "Computing diff of {} against {:?}",
+        workspace_path.display(),
+        target
+    );
+    let repo = Repository::open(workspace_path)?;
+    let head = repo.head()?.peel_to_commit()?;
+
+    let (tree, compared_with) = match (target, base) {
+        (DiffTarget::Base, Some(base)) => {
+            let base_commit = repo.revparse_single(base)?.peel_to_commit()?;
+            let merge_base = repo.merge_base(head.id(), base_commit.id())?;
+            let short_id = merge_base.to_string()[..7].to_string();
+            (
+                repo.find_commit(merge_base)?.tree()?,
+                format!("{base} (merge base {short_id})"),
+            )
+        }
+        (DiffTarget::Base, None) => {
+            return Err(git2::Error::from_str("base branch is unknown"));
+        }
+        (DiffTarget::Head, _) => (head.tree()?, "HEAD".to_string()),
+    };
+
+    let mut options = DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    let mut diff = repo.diff_tree_to_workdir_with_index(Some(&tree), Some(&mut options))?;
+    diff.find_similar(None)?;
+
+    let mut files = Vec::new();
+    for index in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(&diff, index)? else {
+            continue;
+        };
+        files.push(file_diff(&patch)?);
+    }
+
+    Ok(WorkspaceDiff {
+        compared_with,
+        files,
+    })
+}
+
+fn file_diff(patch: &Patch) -> Result<FileDiff, git2::Error> {
+    let delta = patch.delta();
+    let path_of = |file: git2::DiffFile| {
+        file.path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default()
+    };
+    let old_path = path_of(delta.old_file());
+    let new_path = path_of(delta.new_file());
+
+    let (status, path) = match delta.status() {
+        Delta::Added | Delta::Untracked => ('A', new_path.clone()),
+        Delta::Deleted => ('D', old_path.clone()),
+        Delta::Renamed => ('R', format!("{old_path} -> {new_path}")),
+        Delta::Copied => ('C', format!("{old_path} -> {new_path}")),
+        Delta::Typechange => ('T', new_path.clone()),
+        _ => ('M', new_path.clone()),
+    };
+
+    let mut lines = vec![
+        DiffLine {
+            kind: DiffLineKind::FileHeader,
+            text: format!("--- a/{old_path}"),
+        },
+        DiffLine {
+            kind: DiffLineKind::FileHeader,
+            text: format!("+++ b/{new_path}"),
+        },
+    ];
+    if delta.flags().is_binary() {
+        lines.push(DiffLine {
+            kind: DiffLineKind::FileHeader,
+            text: "Binary file differs".to_string(),
+        });
+    }
+
+    let (mut additions, mut deletions) = (0, 0);
+    for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_index)?;
+        lines.push(DiffLine {
+            kind: DiffLineKind::HunkHeader,
+            text: String::from_utf8_lossy(hunk.header())
+                .trim_end()
+                .to_string(),
+        });
+        for line_index in 0..line_count {
+            let line = patch.line_in_hunk(hunk_index, line_index)?;
+            let content = String::from_utf8_lossy(line.content());
+            let content = content.trim_end_matches(['\n', '\r']);
+            let (kind, prefix) = match line.origin() {
+                '+' => {
+                    additions += 1;
+                    (DiffLineKind::Addition, '+')
+                }
+                '-' => {
+                    deletions += 1;
+                    (DiffLineKind::Deletion, '-')
+                }
+                // "\ No newline at end of file" markers already carry their text,
+                // surrounded by newlines
+                '=' | '>' | '<' => {
+                    lines.push(DiffLine {
+                        kind: DiffLineKind::Context,
+                        text: content.trim().to_string(),
+                    });
+                    continue;
+                }
+                _ => (DiffLineKind::Context, ' '),
+            };
+            lines.push(DiffLine {
+                kind,
+                text: format!("{prefix}{content}"),
+            });
+        }
+    }
+
+    Ok(FileDiff {
+        path,
+        status,
+        additions,
+        deletions,
+        lines,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    /// Repository on "main" with a "feature" branch that has one commit and local changes
+    fn setup_repo(temp_dir: &TempDir) -> &Path {
+        let root = temp_dir.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        git(root, &["config", "user.name", "test"]);
+        git(root, &["config", "user.email", "test@example.com"]);
+        fs::write(root.join("README.md"), "line 1\nline 2\n").unwrap();
+        fs::write(root.join("old.txt"), "old\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "initial"]);
+
+        git(root, &["checkout", "-q", "-b", "feature"]);
+        fs::write(root.join("committed.txt"), "committed\n").unwrap();
+        git(root, &["add", "committed.txt"]);
+        git(root, &["commit", "-q", "-m", "feature work"]);
+
+        fs::write(root.join("README.md"), "line 1\nchanged\n").unwrap();
+        fs::remove_file(root.join("old.txt")).unwrap();
+        fs::write(root.join("untracked.txt"), "new\n").unwrap();
+        root
+    }
+
+    fn summary(diff: &WorkspaceDiff) -> Vec<String> {
+        let mut files: Vec<String> = diff
+            .files
+            .iter()
+            .map(|file| format!("{} {}", file.status, file.path))
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn test_diff_against_head() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = setup_repo(&temp_dir);
+
+        let diff = workspace_diff(root, Some("main"), DiffTarget::Head).unwrap();
+        assert_eq!(diff.compared_with, "HEAD");
+        assert_eq!(
+            summary(&diff),
+            vec!["A untracked.txt", "D old.txt", "M README.md"]
+        );
+
+        let readme = diff.files.iter().find(|f| f.path == "README.md").unwrap();
+        assert_eq!((readme.additions, readme.deletions), (1, 1));
+        let kinds: Vec<(DiffLineKind, &str)> = readme
+            .lines
+            .iter()
+            .map(|line| (line.kind, line.text.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DiffLineKind::FileHeader, "--- a/README.md"),
+                (DiffLineKind::FileHeader, "+++ b/README.md"),
+                (DiffLineKind::HunkHeader, "@@ -1,2 +1,2 @@"),
+                (DiffLineKind::Context, " line 1"),
+                (DiffLineKind::Deletion, "-line 2"),
+                (DiffLineKind::Addition, "+changed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_against_base_includes_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = setup_repo(&temp_dir);
+
+        let diff = workspace_diff(root, Some("main"), DiffTarget::Base).unwrap();
+        assert!(diff.compared_with.starts_with("main (merge base "));
+        assert_eq!(diff.compared_with.len(), "main (merge base 0123456)".len());
+        assert_eq!(
+            summary(&diff),
+            vec![
+                "A committed.txt",
+                "A untracked.txt",
+                "D old.txt",
+                "M README.md"
+            ]
+        );
+
+        assert!(workspace_diff(root, None, DiffTarget::Base).is_err());
+    }
+
+    #[test]
+    fn test_diff_without_trailing_newline() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = setup_repo(&temp_dir);
+        fs::write(root.join("README.md"), "line 1\nline 2").unwrap();
+
+        let diff = workspace_diff(root, Some("main"), DiffTarget::Head).unwrap();
+        let readme = diff.files.iter().find(|f| f.path == "README.md").unwrap();
+        assert_eq!((readme.additions, readme.deletions), (1, 1));
+        let texts: Vec<&str> = readme.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "--- a/README.md",
+                "+++ b/README.md",
+                "@@ -1,2 +1,2 @@",
+                " line 1",
+                "-line 2",
+                "+line 2",
+                "\\ No newline at end of file",
+            ]
+        );
+    }
+}
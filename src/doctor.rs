@@ -0,0 +1,463 @@
+use crate::config::WorkspaceSettings;
+use crate::error::{GitwsError, GitwsResult};
+use crate::workspace::lock_reason;
+use git2::{BranchType, Repository, WorktreePruneOptions};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, error};
+
+/// Something wrong with the worktrees of a repository
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// Worktree moved by hand into base_dir; git still has it at the old path
+    MovedWorktree { name: String, path: String },
+    /// Registered worktree whose directory no longer exists
+    MissingDirectory {
+        name: String,
+        path: String,
+        /// Reason when the registration is locked, which keeps `git worktree prune` away
+        lock_reason: Option<String>,
+    },
+    /// Worktree locked against pruning and removal; reported for information
+    Locked { name: String, reason: String },
+    /// Branch with the workspace prefix that no worktree has checked out
+    OrphanBranch { branch: String, merged: bool },
+    /// Directory in base_dir that is not a worktree of this repository
+    UnregisteredDirectory { path: String },
+}
+
+impl Problem {
+    pub fn description(&self) -> String {
+        match self {
+            Problem::MovedWorktree { name, path } => {
+                format!("Worktree {name} was moved to {path}")
+            }
+            Problem::MissingDirectory {
+                name,
+                path,
+                lock_reason,
+            } => match lock_reason {
+                Some(reason) => format!("Missing directory: {name} ({path}), locked: {reason}"),
+                None => format!("Missing directory: {name} ({path})"),
+            },
+            Problem::Locked { name, reason } => format!("Locked: {name} ({reason})"),
+            Problem::OrphanBranch {
+                branch,
+                merged: true,
+            } => format!("Orphan branch: {branch} (merged)"),
+            Problem::OrphanBranch {
+                branch,
+                merged: false,
+            } => format!("Orphan branch: {branch} (unmerged commits)"),
+            Problem::UnregisteredDirectory { path } => {
+                format!("Not a worktree: {path}")
+            }
+        }
+    }
+
+    /// What `fix` does, or None when the problem is left to the user
+    pub fn fix_label(&self) -> Option<&'static str> {
+        match self {
+            Problem::MovedWorktree { .. } => Some("repair the worktree links"),
+            Problem::MissingDirectory {
+                lock_reason: Some(_),
+                ..
+            } => Some("unlock and prune the registration"),
+            Problem::MissingDirectory { .. } => Some("prune the registration"),
+            Problem::OrphanBranch { merged: true, .. } => Some("delete the branch"),
+            Problem::Locked { .. }
+            | Problem::OrphanBranch { merged: false, .. }
+            | Problem::UnregisteredDirectory { .. } => None,
+        }
+    }
+
+    /// Hint for problems without an automatic fix
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            Problem::Locked { name, .. } => Some(format!("gitws unlock {name} when it is done")),
+            Problem::OrphanBranch {
+                branch,
+                merged: false,
+            } => Some(format!(
+                "git branch -D {branch} once it is no longer needed"
+            )),
+            Problem::UnregisteredDirectory { .. } => {
+                Some("move it out of base_dir or remove it".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Problems that need attention (locks are intentional)
+    pub fn is_issue(&self) -> bool {
+        !matches!(self, Problem::Locked { .. })
+    }
+}
+
+/// Look for broken and stale worktree registrations, orphan workspace branches and
+/// stray directories in base_dir. Fixes are meant to be applied in the returned order:
+/// repairs and prunes come before the branches they free.
+pub fn diagnose(repo: &Repository, settings: &WorkspaceSettings) -> GitwsResult<Vec<Problem>> {
+    let common_dir = repo.commondir().to_path_buf();
+    let main_repo = Repository::open(&common_dir).map_err(|e| {
+        error!("Failed to open main repository: {}", e);
+        GitwsError::git(format!("Repository open error: {e}"))
+    })?;
+    let worktree_names = repo.worktrees().map_err(|e| {
+        error!("Failed to get worktree list: {}", e);
+        GitwsError::git(format!("Worktree list retrieval error: {e}"))
+    })?;
+
+    let mut registered_paths = HashSet::new();
+    if let Some(workdir) = main_repo.workdir() {
+        registered_paths.extend(workdir.canonicalize().ok());
+    }
+    let mut checked_out = HashSet::new();
+    if let Some(branch) = head_branch(&common_dir) {
+        checked_out.insert(branch);
+    }
+
+    // Worktrees moved into base_dir by hand: `git worktree repair` reconnects them
+    let mut unregistered = Vec::new();
+    let mut moved = Vec::new();
+    let worktree_paths: Vec<PathBuf> = worktree_names
+        .iter()
+        .flatten()
+        .filter_map(|name| main_repo.find_worktree(name).ok())
+        .filter_map(|worktree| worktree.path().canonicalize().ok())
+        .collect();
+    registered_paths.extend(worktree_paths);
+    if let Ok(entries) = fs::read_dir(&settings.base_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir()
+                || path
+                    .canonicalize()
+                    .is_ok_and(|path| registered_paths.contains(&path))
+            {
+                continue;
+            }
+            match admin_dir_name(&path, &common_dir) {
+                // base_dir is relative to the current directory, `git worktree repair` isn't run there
+                Some(name) => moved.push((
+                    name,
+                    path.canonicalize()
+                        .unwrap_or(path)
+                        .to_string_lossy()
+                        .into_owned(),
+                )),
+                None => unregistered.push(path.to_string_lossy().into_owned()),
+            }
+        }
+    }
+
+    let mut problems: Vec<Problem> = moved
+        .iter()
+        .map(|(name, path)| Problem::MovedWorktree {
+            name: name.clone(),
+            path: path.clone(),
+        })
+        .collect();
+
+    for name in worktree_names.iter().flatten() {
+        let Ok(worktree) = main_repo.find_worktree(name) else {
+            continue;
+        };
+        let lock_reason = lock_reason(&worktree);
+        let is_moved = moved.iter().any(|(moved_name, _)| moved_name == name);
+        if !worktree.path().exists() && !is_moved {
+            problems.push(Problem::MissingDirectory {
+                name: name.to_string(),
+                path: worktree.path().to_string_lossy().into_owned(),
+                lock_reason,
+            });
+            // Its branch becomes an orphan once the registration is pruned
+            continue;
+        }
+        if let Some(branch) = head_branch(&common_dir.join("worktrees").join(name)) {
+            checked_out.insert(branch);
+        }
+        if let Some(reason) = lock_reason {
+            problems.push(Problem::Locked {
+                name: name.to_string(),
+                reason,
+            });
+        }
+    }
+
+    if !settings.branch_prefix.is_empty() {
+        let head = main_repo.head().ok().and_then(|head| head.target());
+        let branches = main_repo.branches(Some(BranchType::Local)).map_err(|e| {
+            error!("Failed to list branches: {}", e);
+            GitwsError::git(format!("Branch list retrieval error: {e}"))
+        })?;
+        for (branch, _) in branches.flatten() {
+            let Ok(Some(name)) = branch.name() else {
+                continue;
+            };
+            if !name.starts_with(&settings.branch_prefix) || checked_out.contains(name) {
+                continue;
+            }
+            let merged = match (head, branch.get().target()) {
+                (Some(head), Some(tip)) => {
+                    head == tip || main_repo.graph_descendant_of(head, tip).unwrap_or(false)
+                }
+                _ => false,
+            };
+            problems.push(Problem::OrphanBranch {
+                branch: name.to_string(),
+                merged,
+            });
+        }
+    }
+
+    problems.extend(
+        unregistered
+            .into_iter()
+            .map(|path| Problem::UnregisteredDirectory { path }),
+    );
+    Ok(problems)
+}
+
+/// Apply the fix of a problem
+pub fn fix(repo: &Repository, problem: &Problem) -> GitwsResult<()> {
+    debug!("Fixing: {}", problem.description());
+    match problem {
+        Problem::MovedWorktree { path, .. } => {
+            let output = Command::new("git")
+                .args(["worktree", "repair", path])
+                .current_dir(repo.commondir())
+                .output()
+                .map_err(|e| GitwsError::git(format!("git worktree repair error: {e}")))?;
+            if !output.status.success() {
+                return Err(GitwsError::git(format!(
+                    "git worktree repair failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(())
+        }
+        Problem::MissingDirectory { name, .. } => {
+            let worktree = repo
+                .find_worktree(name)
+                .map_err(|e| GitwsError::git(format!("Worktree not found: {name} - {e}")))?;
+            let mut options = WorktreePruneOptions::new();
+            options.locked(true);
+            worktree
+                .prune(Some(&mut options))
+                .map_err(|e| GitwsError::git(format!("Failed to prune {name}: {e}")))
+        }
+        Problem::OrphanBranch { branch, .. } => repo
+            .find_branch(branch, BranchType::Local)
+            .and_then(|mut branch| branch.delete())
+            .map_err(|e| GitwsError::git(format!("Failed to delete branch {branch}: {e}"))),
+        Problem::Locked { .. } | Problem::UnregisteredDirectory { .. } => Ok(()),
+    }
+}
+
+/// Branch a HEAD file in the given git directory points to
+fn head_branch(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    head.trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(str::to_string)
+}
+
+/// Name of the worktree admin directory of this repository a checkout's `.git` file points to
+fn admin_dir_name(path: &Path, common_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(path.join(".git")).ok()?;
+    let gitdir = path.join(content.trim().strip_prefix("gitdir:")?.trim());
+    let gitdir = gitdir.canonicalize().ok()?;
+    let worktrees_dir = common_dir.join("worktrees").canonicalize().ok()?;
+    if gitdir.parent()? != worktrees_dir {
+        return None;
+    }
+    gitdir.file_name()?.to_str().map(str::to_string)
+}
+
+/// Print the findings; returns the number of problems that need attention
+pub fn print_report(problems: &[Problem]) -> usize {
+    for problem in problems {
+        let icon = if problem.is_issue() {
+            "⚠️ "
+        } else {
+            "🔒"
+        };
+        match (problem.fix_label(), problem.hint()) {
+            (Some(fix), _) => println!("  {icon} {} → {fix}", problem.description()),
+            (None, Some(hint)) => println!("  {icon} {} ({hint})", problem.description()),
+            (None, None) => println!("  {icon} {}", problem.description()),
+        }
+    }
+    problems.iter().filter(|problem| problem.is_issue()).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn setup_repo(temp_dir: &TempDir) -> (PathBuf, PathBuf) {
+        let main_path = temp_dir.path().join("main");
+        let base_dir = temp_dir.path().join("workspaces");
+        fs::create_dir_all(&main_path).unwrap();
+        fs::create_dir_all(&base_dir).unwrap();
+        git(&main_path, &["init", "-q", "-b", "main"]);
+        git(&main_path, &["config", "user.name", "test"]);
+        git(&main_path, &["config", "user.email", "test@example.com"]);
+        git(
+            &main_path,
+            &["commit", "-q", "--allow-empty", "-m", "initial"],
+        );
+        (main_path, base_dir)
+    }
+
+    #[test]
+    fn test_diagnose_and_fix() {
+        let temp_dir = TempDir::new().unwrap();
+        let (main_path, base_dir) = setup_repo(&temp_dir);
+
+        let add = |name: &str| {
+            let path = base_dir.join(name);
+            git(
+                &main_path,
+                &[
+                    "worktree",
+                    "add",
+                    "-q",
+                    "-b",
+                    &format!("work/{name}"),
+                    path.to_str().unwrap(),
+                ],
+            );
+            path
+        };
+        add("healthy");
+        fs::remove_dir_all(add("deleted")).unwrap();
+        let locked = add("locked");
+        git(
+            &main_path,
+            &["worktree", "lock", "--reason", "agent", "locked"],
+        );
+        let moved = add("moved");
+        fs::rename(&moved, base_dir.join("moved-by-hand")).unwrap();
+        git(&main_path, &["branch", "work/merged"]);
+        git(&main_path, &["checkout", "-q", "-b", "work/unmerged"]);
+        git(&main_path, &["commit", "-q", "--allow-empty", "-m", "wip"]);
+        git(&main_path, &["checkout", "-q", "main"]);
+        fs::create_dir_all(base_dir.join("stray")).unwrap();
+
+        let settings = WorkspaceSettings {
+            base_dir: base_dir.to_string_lossy().into_owned(),
+            ..crate::config::WorkspaceConfig::default().workspace
+        };
+        let repo = Repository::open(&main_path).unwrap();
+        let problems = diagnose(&repo, &settings).unwrap();
+        let descriptions: Vec<String> = problems.iter().map(Problem::description).collect();
+        assert_eq!(problems.len(), 7, "{descriptions:#?}");
+        assert!(matches!(&problems[0], Problem::MovedWorktree { name, .. } if name == "moved"));
+        assert!(problems.contains(&Problem::MissingDirectory {
+            name: "deleted".to_string(),
+            path: base_dir.join("deleted").to_string_lossy().into_owned(),
+            lock_reason: None,
+        }));
+        assert!(problems.contains(&Problem::Locked {
+            name: "locked".to_string(),
+            reason: "agent".to_string(),
+        }));
+        assert!(problems.contains(&Problem::OrphanBranch {
+            branch: "work/merged".to_string(),
+            merged: true,
+        }));
+        // Freed by pruning the missing worktree
+        assert!(problems.contains(&Problem::OrphanBranch {
+            branch: "work/deleted".to_string(),
+            merged: true,
+        }));
+        assert!(problems.contains(&Problem::OrphanBranch {
+            branch: "work/unmerged".to_string(),
+            merged: false,
+        }));
+        assert!(problems.contains(&Problem::UnregisteredDirectory {
+            path: base_dir.join("stray").to_string_lossy().into_owned(),
+        }));
+
+        for problem in problems.iter().filter(|p| p.fix_label().is_some()) {
+            fix(&repo, problem).unwrap();
+        }
+
+        // What is left needs the user
+        let problems = diagnose(&repo, &settings).unwrap();
+        let descriptions: Vec<String> = problems.iter().map(Problem::description).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "Locked: locked (agent)".to_string(),
+                "Orphan branch: work/unmerged (unmerged commits)".to_string(),
+                format!("Not a worktree: {}", base_dir.join("stray").display()),
+            ]
+        );
+        assert!(locked.exists());
+        assert!(Repository::open(base_dir.join("moved-by-hand")).is_ok());
+    }
+
+    #[test]
+    fn test_repair_with_relative_base_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let (main_path, base_dir) = setup_repo(&temp_dir);
+        let old_path = temp_dir.path().join("old");
+        git(
+            &main_path,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "work/moved",
+                old_path.to_str().unwrap(),
+            ],
+        );
+        fs::rename(&old_path, base_dir.join("moved")).unwrap();
+
+        // base_dir as the configuration has it: relative to the current directory
+        let current_dir = std::env::current_dir().unwrap().canonicalize().unwrap();
+        let absolute = base_dir.canonicalize().unwrap();
+        let mut relative = PathBuf::new();
+        for _ in current_dir.components().skip(1) {
+            relative.push("..");
+        }
+        relative.push(absolute.strip_prefix("/").unwrap());
+        let settings = WorkspaceSettings {
+            base_dir: relative.to_string_lossy().into_owned(),
+            ..crate::config::WorkspaceConfig::default().workspace
+        };
+
+        let repo = Repository::open(&main_path).unwrap();
+        let problems = diagnose(&repo, &settings).unwrap();
+        assert_eq!(
+            problems,
+            vec![Problem::MovedWorktree {
+                name: "old".to_string(),
+                path: absolute.join("moved").to_string_lossy().into_owned(),
+            }]
+        );
+        fix(&repo, &problems[0]).unwrap();
+        assert!(diagnose(&repo, &settings).unwrap().is_empty());
+    }
+}
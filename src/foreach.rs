@@ -0,0 +1,301 @@
+use crate::error::{GitwsError, GitwsResult};
+use crate::workspace::{build_workspace_command, exit_code, WorkspaceInfo};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// How `foreach` runs the command
+#[derive(Debug, Clone)]
+pub struct ForeachOptions {
+    /// Number of workspaces processed at the same time
+    pub parallel: usize,
+    /// Print each workspace's output as one block when it finishes
+    /// instead of interleaving prefixed lines
+    pub grouped: bool,
+}
+
+impl Default for ForeachOptions {
+    fn default() -> Self {
+        Self {
+            parallel: 1,
+            grouped: false,
+        }
+    }
+}
+
+/// Result of running the command in one workspace
+#[derive(Debug)]
+pub struct ForeachResult {
+    pub workspace: String,
+    /// Exit code, or the error message if the command could not be started
+    pub outcome: Result<i32, String>,
+    pub duration: Duration,
+}
+
+impl ForeachResult {
+    pub fn is_success(&self) -> bool {
+        matches!(self.outcome, Ok(0))
+    }
+}
+
+/// Run a command in every workspace and collect the results in workspace order
+pub fn run_foreach(
+    workspaces: &[WorkspaceInfo],
+    command: &[String],
+    env: &BTreeMap<String, String>,
+    options: &ForeachOptions,
+) -> GitwsResult<Vec<ForeachResult>> {
+    if command.is_empty() {
+        return Err(GitwsError::workspace("No command specified"));
+    }
+
+    let parallel = options.parallel.clamp(1, workspaces.len().max(1));
+    debug!(
+        "Running {:?} in {} workspaces ({} in parallel)",
+        command,
+        workspaces.len(),
+        parallel
+    );
+
+    let prefix_width = workspaces
+        .iter()
+        .map(|ws| ws.task_name().chars().count())
+        .max()
+        .unwrap_or(0);
+    let next_index = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<ForeachResult>>> =
+        Mutex::new((0..workspaces.len()).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..parallel {
+            scope.spawn(|| loop {
+                let index = next_index.fetch_add(1, Ordering::SeqCst);
+                let Some(workspace) = workspaces.get(index) else {
+                    break;
+                };
+                let result =
+                    run_in_workspace(workspace, command, env, options, parallel > 1, prefix_width);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    Ok(results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+fn run_in_workspace(
+    workspace: &WorkspaceInfo,
+    command: &[String],
+    env: &BTreeMap<String, String>,
+    options: &ForeachOptions,
+    detach_stdin: bool,
+    prefix_width: usize,
+) -> ForeachResult {
+    let started = Instant::now();
+    let prefix = format!("[{:width$}]", workspace.task_name(), width = prefix_width);
+
+    let outcome = build_workspace_command(workspace, command, env)
+        .map_err(|e| e.to_string())
+        .and_then(|mut cmd| {
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+            if detach_stdin {
+                cmd.stdin(Stdio::null());
+            }
+            cmd.spawn().map_err(|e| e.to_string())
+        })
+        .and_then(|mut child| {
+            let stdout = child.stdout.take();
+            let stderr = child.stderr.take();
+            if options.grouped {
+                let (out, err) = thread::scope(|scope| {
+                    let out = scope.spawn(|| read_all(stdout));
+                    let err = read_all(stderr);
+                    (out.join().unwrap_or_default(), err)
+                });
+                let status = child.wait().map_err(|e| e.to_string())?;
+                print_group(&prefix, &out, &err);
+                Ok(exit_code(&status))
+            } else {
+                thread::scope(|scope| {
+                    scope.spawn(|| forward_lines(stdout, &prefix, false));
+                    forward_lines(stderr, &prefix, true);
+                });
+                let status = child.wait().map_err(|e| e.to_string())?;
+                Ok(exit_code(&status))
+            }
+        });
+
+    if let Err(e) = &outcome {
+        eprintln!("{prefix} ❌ Command execution error: {e}");
+    }
+
+    ForeachResult {
+        workspace: workspace.name.clone(),
+        outcome,
+        duration: started.elapsed(),
+    }
+}
+
+fn read_all<R: Read>(reader: Option<R>) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    if let Some(mut reader) = reader {
+        let _ = reader.read_to_end(&mut buffer);
+    }
+    buffer
+}
+
+/// Copy output line by line, prefixed with the workspace name
+fn forward_lines<R: Read>(reader: Option<R>, prefix: &str, to_stderr: bool) {
+    let Some(reader) = reader else {
+        return;
+    };
+    for line in BufReader::new(reader).split(b'\n').map_while(Result::ok) {
+        let line = String::from_utf8_lossy(&line);
+        if to_stderr {
+            eprintln!("{prefix} {line}");
+        } else {
+            println!("{prefix} {line}");
+        }
+    }
+}
+
+fn print_group(prefix: &str, stdout: &[u8], stderr: &[u8]) {
+    // Hold the lock so that groups from parallel workers don't mix
+    let mut out = std::io::stdout().lock();
+    let _ = writeln!(out, "── {prefix} ──");
+    for output in [stdout, stderr] {
+        let _ = out.write_all(output);
+        if !output.is_empty() && !output.ends_with(b"\n") {
+            let _ = writeln!(out);
+        }
+    }
+    let _ = out.flush();
+}
+
+/// Print the final pass/fail table
+pub fn print_summary(results: &[ForeachResult]) {
+    let name_width = results
+        .iter()
+        .map(|r| r.workspace.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    println!("\n📋 Summary:");
+    for result in results {
+        let status = match &result.outcome {
+            Ok(0) => "✅ pass".to_string(),
+            Ok(code) => format!("❌ fail (exit code: {code})"),
+            Err(e) => format!("❌ error ({e})"),
+        };
+        println!(
+            "  {:width$}  {:>7.1}s  {}",
+            result.workspace,
+            result.duration.as_secs_f64(),
+            status,
+            width = name_width
+        );
+    }
+
+    let passed = results.iter().filter(|r| r.is_success()).count();
+    println!("\n{passed} passed, {} failed", results.len() - passed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_workspaces(temp_dir: &TempDir, names: &[&str]) -> Vec<WorkspaceInfo> {
+        names
+            .iter()
+            .map(|name| {
+                let path = temp_dir.path().join(name);
+                fs::create_dir_all(&path).unwrap();
+                WorkspaceInfo {
+                    name: name.to_string(),
+                    path: path.to_str().unwrap().to_string(),
+                    branch: format!("work/{name}"),
+                    locked: None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_run_foreach_sequential() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspaces = create_test_workspaces(&temp_dir, &["ws1", "ws2"]);
+        let command = vec!["echo $GITWS_WORKSPACE > name.txt".to_string()];
+
+        let results = run_foreach(
+            &workspaces,
+            &command,
+            &BTreeMap::new(),
+            &ForeachOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(ForeachResult::is_success));
+        for ws in &workspaces {
+            let content = fs::read_to_string(format!("{}/name.txt", ws.path)).unwrap();
+            assert_eq!(content.trim(), ws.name);
+        }
+    }
+
+    #[test]
+    fn test_run_foreach_parallel_keeps_order_and_reports_failures() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspaces = create_test_workspaces(&temp_dir, &["ok1", "fail", "ok2"]);
+        let command = vec!["test \"$GITWS_WORKSPACE\" != fail || exit 7".to_string()];
+        let options = ForeachOptions {
+            parallel: 3,
+            grouped: true,
+        };
+
+        let results = run_foreach(&workspaces, &command, &BTreeMap::new(), &options).unwrap();
+
+        let names: Vec<&str> = results.iter().map(|r| r.workspace.as_str()).collect();
+        assert_eq!(names, vec!["ok1", "fail", "ok2"]);
+        assert!(results[0].is_success());
+        assert_eq!(results[1].outcome, Ok(7));
+        assert!(results[2].is_success());
+    }
+
+    #[test]
+    fn test_run_foreach_spawn_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let workspaces = create_test_workspaces(&temp_dir, &["ws1"]);
+        let command = vec![
+            "gitws-command-that-does-not-exist".to_string(),
+            "arg".to_string(),
+        ];
+
+        let results = run_foreach(
+            &workspaces,
+            &command,
+            &BTreeMap::new(),
+            &ForeachOptions::default(),
+        )
+        .unwrap();
+        assert!(results[0].outcome.is_err());
+        assert!(!results[0].is_success());
+    }
+
+    #[test]
+    fn test_run_foreach_empty_command() {
+        let result = run_foreach(&[], &[], &BTreeMap::new(), &ForeachOptions::default());
+        assert!(result.is_err());
+    }
+}
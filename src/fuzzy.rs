@@ -0,0 +1,121 @@
+/// Result of a fuzzy match
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    /// Character indices in the text that matched the pattern
+    pub indices: Vec<usize>,
+}
+
+/// Match `pattern` against `text` as a case-insensitive subsequence
+///
+/// Consecutive matches and matches at word boundaries (after `-`, `_`, `/`, `.` or space)
+/// score higher, gaps between matches lower the score. Returns None if not every
+/// pattern character is found in order.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            indices: Vec::new(),
+        });
+    }
+
+    let text: Vec<char> = text.chars().collect();
+
+    // Try every occurrence of the first pattern character as a starting point
+    // and keep the best scoring alignment
+    text.iter()
+        .enumerate()
+        .filter(|(_, c)| char_eq(**c, pattern[0]))
+        .filter_map(|(start, _)| match_from(&pattern, &text, start))
+        .max_by_key(|m| m.score)
+}
+
+fn match_from(pattern: &[char], text: &[char], start: usize) -> Option<FuzzyMatch> {
+    let mut indices = Vec::with_capacity(pattern.len());
+    let mut score: i64 = 0;
+    let mut pattern_pos = 0;
+    let mut last_match: Option<usize> = None;
+
+    for (i, c) in text.iter().enumerate().skip(start) {
+        if pattern_pos == pattern.len() {
+            break;
+        }
+        if !char_eq(*c, pattern[pattern_pos]) {
+            continue;
+        }
+
+        score += 1;
+        if let Some(last) = last_match {
+            if last + 1 == i {
+                score += 5;
+            } else {
+                score -= (i - last - 1).min(5) as i64;
+            }
+        }
+        if i == 0 || matches!(text[i - 1], '-' | '_' | '/' | '.' | ' ') {
+            score += 3;
+        }
+
+        indices.push(i);
+        last_match = Some(i);
+        pattern_pos += 1;
+    }
+
+    if pattern_pos == pattern.len() {
+        Some(FuzzyMatch { score, indices })
+    } else {
+        None
+    }
+}
+
+fn char_eq(text_char: char, pattern_char: char) -> bool {
+    text_char.to_lowercase().eq(std::iter::once(pattern_char))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match_subsequence() {
+        let result = fuzzy_match("auth", "20250625-120000-feature-authentication").unwrap();
+        assert_eq!(result.indices, vec![24, 25, 26, 27]);
+
+        let result = fuzzy_match("fa", "feature-auth").unwrap();
+        assert_eq!(result.indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_fuzzy_match_no_match() {
+        assert!(fuzzy_match("xyz", "feature-auth").is_none());
+        // Order matters
+        assert!(fuzzy_match("htua", "feature-auth").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match_case_insensitive() {
+        assert!(fuzzy_match("AUTH", "feature-auth").is_some());
+        assert!(fuzzy_match("auth", "Feature-AUTH").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_match_empty_pattern() {
+        let result = fuzzy_match("", "anything").unwrap();
+        assert_eq!(result.score, 0);
+        assert!(result.indices.is_empty());
+    }
+
+    #[test]
+    fn test_fuzzy_match_scoring() {
+        // Consecutive matches score higher than scattered ones
+        let consecutive = fuzzy_match("login", "bugfix-login").unwrap();
+        let scattered = fuzzy_match("login", "large-object-graph-in-node").unwrap();
+        assert!(consecutive.score > scattered.score);
+
+        // Word boundary matches score higher
+        let boundary = fuzzy_match("api", "fix-api").unwrap();
+        let inner = fuzzy_match("api", "rapid").unwrap();
+        assert!(boundary.score > inner.score);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::config::GcSettings;
+use crate::workspace::{BranchTracking, WorkspaceInfo, WorkspaceManager, WorkspaceStatus};
+use chrono::{DateTime, Local};
+use std::path::Path;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Why a workspace is due for removal
+#[derive(Debug, Clone, PartialEq)]
+pub enum GcReason {
+    /// Every commit of the branch is in the base branch
+    Merged(String),
+    /// Created this many days ago
+    Old(i64),
+    /// No activity for this many days
+    Idle(i64),
+}
+
+impl GcReason {
+    pub fn label(&self) -> String {
+        match self {
+            GcReason::Merged(base) => format!("merged into {base}"),
+            GcReason::Old(days) => format!("created {days} days ago"),
+            GcReason::Idle(days) => format!("idle for {days} days"),
+        }
+    }
+}
+
+/// What gc looks at to decide about one workspace
+#[derive(Debug, Default)]
+pub struct GcFacts {
+    pub status: Option<WorkspaceStatus>,
+    pub tracking: BranchTracking,
+    pub age_days: Option<i64>,
+    pub idle_days: Option<i64>,
+    /// Lock reason when the worktree is locked
+    pub locked: Option<String>,
+    /// gc was started from inside the workspace
+    pub is_current_dir: bool,
+}
+
+impl GcFacts {
+    pub fn gather(
+        workspace_manager: &WorkspaceManager,
+        workspace: &WorkspaceInfo,
+        now: DateTime<Local>,
+    ) -> Self {
+        let is_current_dir = std::env::current_dir()
+            .and_then(|dir| dir.canonicalize())
+            .ok()
+            .zip(Path::new(&workspace.path).canonicalize().ok())
+            .is_some_and(|(dir, path)| dir.starts_with(path));
+        Self {
+            status: workspace_manager.get_workspace_status(workspace),
+            tracking: workspace_manager.get_branch_tracking(workspace),
+            age_days: workspace
+                .created_at()
+                .map(|created| (now.naive_local() - created).num_days()),
+            idle_days: workspace_manager
+                .get_last_activity(workspace)
+                .map(|activity| (now.timestamp() - activity) / SECONDS_PER_DAY),
+            locked: workspace.locked.clone(),
+            is_current_dir,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GcVerdict {
+    Keep,
+    Remove(Vec<GcReason>),
+    /// Due for removal but holding work that would be lost
+    Protected {
+        reasons: Vec<GcReason>,
+        blocker: String,
+    },
+}
+
+/// Decide whether a workspace is merged, too old or too idle, and whether it is safe to remove
+pub fn evaluate(facts: &GcFacts, settings: &GcSettings) -> GcVerdict {
+    let mut reasons = Vec::new();
+    if facts.tracking.merged {
+        let base = facts.tracking.base.clone().unwrap_or_default();
+        reasons.push(GcReason::Merged(base));
+    }
+    if let (Some(max), Some(days)) = (settings.max_age_days, facts.age_days) {
+        if days > max as i64 {
+            reasons.push(GcReason::Old(days));
+        }
+    }
+    if let (Some(max), Some(days)) = (settings.max_idle_days, facts.idle_days) {
+        if days > max as i64 {
+            reasons.push(GcReason::Idle(days));
+        }
+    }
+    if reasons.is_empty() {
+        return GcVerdict::Keep;
+    }
+
+    let blocker = match &facts.status {
+        _ if facts.locked.is_some() => Some("locked".to_string()),
+        None => Some("status unavailable".to_string()),
+        Some(status) if !status.is_clean() => Some(status.label().to_lowercase()),
+        Some(_) if has_unpushed_work(&facts.tracking) => Some("unpushed commits".to_string()),
+        Some(_) if facts.is_current_dir => Some("current directory".to_string()),
+        Some(_) => None,
+    };
+    match blocker {
+        Some(blocker) => GcVerdict::Protected { reasons, blocker },
+        None => GcVerdict::Remove(reasons),
+    }
+}
+
+/// Commits that exist neither upstream nor in the base branch
+fn has_unpushed_work(tracking: &BranchTracking) -> bool {
+    !tracking.pushed && !tracking.base_counts.is_some_and(|counts| counts.ahead == 0)
+}
+
+fn reasons_text(reasons: &[GcReason]) -> String {
+    reasons
+        .iter()
+        .map(GcReason::label)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// List the workspaces gc would remove and the ones it leaves alone
+pub fn print_plan(verdicts: &[(WorkspaceInfo, GcVerdict)]) {
+    for (workspace, verdict) in verdicts {
+        match verdict {
+            GcVerdict::Keep => {}
+            GcVerdict::Remove(reasons) => {
+                println!("  🗑️  {} ({})", workspace.name, reasons_text(reasons))
+            }
+            GcVerdict::Protected { reasons, blocker } => println!(
+                "  ⏭️  {}: kept, {blocker} ({})",
+                workspace.name,
+                reasons_text(reasons)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::AheadBehind;
+
+    fn merged_facts() -> GcFacts {
+        GcFacts {
+            status: Some(WorkspaceStatus::default()),
+            tracking: BranchTracking {
+                base: Some("main".to_string()),
+                base_counts: Some(AheadBehind {
+                    ahead: 0,
+                    behind: 3,
+                }),
+                merged: true,
+                ..Default::default()
+            },
+            age_days: Some(2),
+            idle_days: Some(1),
+            locked: None,
+            is_current_dir: false,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_reasons() {
+        let settings = GcSettings {
+            max_age_days: Some(30),
+            max_idle_days: Some(14),
+        };
+        assert_eq!(
+            evaluate(&merged_facts(), &settings),
+            GcVerdict::Remove(vec![GcReason::Merged("main".to_string())])
+        );
+
+        // Unmerged but pushed, old and idle
+        let facts = GcFacts {
+            tracking: BranchTracking {
+                base_counts: Some(AheadBehind {
+                    ahead: 2,
+                    behind: 0,
+                }),
+                pushed: true,
+                ..Default::default()
+            },
+            age_days: Some(45),
+            idle_days: Some(20),
+            ..merged_facts()
+        };
+        assert_eq!(
+            evaluate(&facts, &settings),
+            GcVerdict::Remove(vec![GcReason::Old(45), GcReason::Idle(20)])
+        );
+        // Without thresholds only merged workspaces are collected
+        assert_eq!(evaluate(&facts, &GcSettings::default()), GcVerdict::Keep);
+    }
+
+    #[test]
+    fn test_evaluate_protects_work() {
+        let settings = GcSettings {
+            max_age_days: None,
+            max_idle_days: Some(7),
+        };
+
+        let dirty = GcFacts {
+            status: Some(WorkspaceStatus {
+                untracked: 1,
+                ..Default::default()
+            }),
+            ..merged_facts()
+        };
+        assert!(matches!(
+            evaluate(&dirty, &settings),
+            GcVerdict::Protected { blocker, .. } if blocker == "modified"
+        ));
+
+        let unpushed = GcFacts {
+            tracking: BranchTracking {
+                base_counts: Some(AheadBehind {
+                    ahead: 1,
+                    behind: 0,
+                }),
+                ..Default::default()
+            },
+            idle_days: Some(30),
+            ..merged_facts()
+        };
+        assert!(matches!(
+            evaluate(&unpushed, &settings),
+            GcVerdict::Protected { blocker, .. } if blocker == "unpushed commits"
+        ));
+
+        // A branch without commits of its own has nothing to lose
+        let untouched = GcFacts {
+            tracking: BranchTracking {
+                base_counts: Some(AheadBehind {
+                    ahead: 0,
+                    behind: 0,
+                }),
+                ..Default::default()
+            },
+            idle_days: Some(30),
+            ..merged_facts()
+        };
+        assert_eq!(
+            evaluate(&untouched, &settings),
+            GcVerdict::Remove(vec![GcReason::Idle(30)])
+        );
+
+        let current = GcFacts {
+            is_current_dir: true,
+            ..merged_facts()
+        };
+        assert!(matches!(
+            evaluate(&current, &settings),
+            GcVerdict::Protected { blocker, .. } if blocker == "current directory"
+        ));
+
+        let locked = GcFacts {
+            locked: Some("agent running".to_string()),
+            ..merged_facts()
+        };
+        assert!(matches!(
+            evaluate(&locked, &settings),
+            GcVerdict::Protected { blocker, .. } if blocker == "locked"
+        ));
+
+        let unreadable = GcFacts {
+            status: None,
+            ..merged_facts()
+        };
+        assert!(matches!(
+            evaluate(&unreadable, &settings),
+            GcVerdict::Protected { .. }
+        ));
+    }
+}
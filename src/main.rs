@@ -1,6 +1,16 @@
 mod cli;
+mod completions;
 mod config;
+mod diff;
+mod doctor;
 mod error;
+mod foreach;
+mod fuzzy;
+mod gc;
+mod shell;
+mod size;
+mod state;
+mod sync;
 mod tui;
 mod utils;
 mod workspace;
@@ -9,7 +19,8 @@ use clap::Parser;
 use cli::{Cli, Commands};
 use config::{generate_template_config, load_config_from_path};
 use error::GitwsError;
-use tracing::{debug, error};
+use std::io::IsTerminal;
+use tracing::{debug, error, warn};
 use workspace::WorkspaceManager;
 
 fn main() {
@@ -20,6 +31,7 @@ fn main() {
         cli.command,
         Commands::List {
             print_path_only: false,
+            json: false,
             ..
         }
     );
@@ -46,6 +58,27 @@ fn main() {
                 }
             }
         }
+        Commands::ShellInit {
+            shell,
+            no_key_bindings,
+        } => {
+            debug!("Printing shell integration for {:?}", shell);
+            print!("{}", shell::init_script(shell, !no_key_bindings));
+            Ok(())
+        }
+        Commands::Completions { shell } => {
+            debug!("Printing completions for {}", shell);
+            print!("{}", completions::completion_script(shell));
+            Ok(())
+        }
+        Commands::Complete { kind } => {
+            // Called on every Tab press: print nothing rather than fail outside a repository
+            let workspaces = WorkspaceManager::new()
+                .and_then(|manager| manager.list_workspaces())
+                .unwrap_or_default();
+            print!("{}", completions::candidates(&workspaces, kind));
+            Ok(())
+        }
         _ => {
             // For other commands, initialize WorkspaceManager
             let workspace_manager = match WorkspaceManager::new() {
@@ -61,7 +94,11 @@ fn main() {
             };
 
             match cli.command {
-                Commands::Start { task_name, config } => {
+                Commands::Start {
+                    task_name,
+                    config,
+                    cd,
+                } => {
                     debug!("Starting workspace creation: {}", task_name);
                     debug!("Using configuration file: {}", config);
 
@@ -73,9 +110,22 @@ fn main() {
                         &config.workspace.branch_prefix,
                         &config.workspace.copy_files,
                         &config.workspace.pre_commands,
+                        config.workspace.copy_source.as_deref(),
                     ) {
                         Ok(info) => {
                             debug!("Workspace creation completed: {}", info.name);
+                            if cd {
+                                if let Err(e) = workspace_manager.record_visit(&info.name) {
+                                    warn!("Failed to record visited workspace: {}", e);
+                                }
+                                match shell::write_cd_target(&info.path) {
+                                    Ok(true) => {}
+                                    Ok(false) => eprintln!(
+                                        "⚠️  --cd needs the shell integration: see gitws shell-init <bash|zsh|fish>"
+                                    ),
+                                    Err(e) => warn!("{}", e),
+                                }
+                            }
                             Ok(())
                         }
                         Err(e) => {
@@ -88,11 +138,16 @@ fn main() {
                 Commands::List {
                     config,
                     print_path_only,
+                    json,
+                    print0,
+                    output_file,
                 } => {
                     debug!("Starting workspace list display");
                     debug!("Using configuration file: {}", config);
 
-                    let _config = load_config_from_path(&config);
+                    let config = load_config_from_path(&config);
+                    let output_file = output_file.as_deref();
+                    let terminator = if print0 { '\0' } else { '\n' };
 
                     if print_path_only {
                         debug!("Executing --path-only mode");
@@ -100,10 +155,42 @@ fn main() {
                         match workspace_manager.list_workspaces() {
                             Ok(workspaces) => {
                                 debug!("Retrieved workspace list: {} items", workspaces.len());
+                                let mut output = String::new();
                                 for workspace in workspaces {
-                                    println!("{}", workspace.path);
+                                    output.push_str(&workspace.path);
+                                    output.push(terminator);
+                                }
+                                write_list_output(output_file, &output)
+                            }
+                            Err(e) => {
+                                error!("Failed to retrieve workspace list: {}", e);
+                                Err(e)
+                            }
+                        }
+                    } else if json {
+                        debug!("Executing --json mode");
+                        match workspace_manager.list_workspaces() {
+                            Ok(workspaces) => {
+                                let entries: Vec<workspace::WorkspaceListEntry> = workspaces
+                                    .iter()
+                                    .map(|ws| workspace::WorkspaceListEntry {
+                                        workspace: ws,
+                                        task: ws.task_name(),
+                                        status: workspace_manager.get_workspace_status(ws),
+                                        tracking: workspace_manager.get_branch_tracking(ws),
+                                    })
+                                    .collect();
+                                match serde_json::to_string_pretty(&entries) {
+                                    Ok(output) => {
+                                        write_list_output(output_file, &format!("{output}\n"))
+                                    }
+                                    Err(e) => {
+                                        error!("Failed to serialize workspace list: {}", e);
+                                        Err(GitwsError::general(format!(
+                                            "JSON serialization error: {e}"
+                                        )))
+                                    }
                                 }
-                                Ok(())
                             }
                             Err(e) => {
                                 error!("Failed to retrieve workspace list: {}", e);
@@ -115,18 +202,34 @@ fn main() {
                         debug!("Starting TUI mode");
                         debug!("Initializing TUI");
 
-                        match run_tui() {
+                        match run_tui(&config) {
                             Ok(Some(selected_path)) => {
                                 debug!("Path selected in TUI: {}", selected_path);
                                 // Output path of workspace selected with Enter key
                                 // Shell function receives this path and executes cd
-                                println!("{selected_path}");
-                                Ok(())
+                                // Under the shell integration the wrapper changes the directory,
+                                // unless the path is captured with $(...) or redirected
+                                if output_file.is_none() && std::io::stdout().is_terminal() {
+                                    shell::write_cd_target(&selected_path)
+                                } else {
+                                    Ok(false)
+                                }
+                                .and_then(|written| {
+                                    if written {
+                                        Ok(())
+                                    } else {
+                                        write_list_output(
+                                            output_file,
+                                            &format!("{selected_path}{terminator}"),
+                                        )
+                                    }
+                                })
                             }
                             Ok(None) => {
                                 debug!("TUI exited normally (no path selected)");
-                                // Exit without selecting anything
-                                Ok(())
+                                // Exit without selecting anything; an output file is still
+                                // truncated so that wrappers don't read a stale path
+                                write_list_output(output_file, "")
                             }
                             Err(e) => {
                                 error!("TUI error occurred: {}", e);
@@ -136,7 +239,270 @@ fn main() {
                         }
                     }
                 }
-                Commands::Init { .. } => {
+                Commands::Cd { workspace, config } => {
+                    debug!("Resolving workspace to change into: {}", workspace);
+                    debug!("Using configuration file: {}", config);
+
+                    let config = load_config_from_path(&config);
+
+                    match resolve_cd_target(&workspace_manager, &workspace, &config) {
+                        Ok(Some(info)) => {
+                            debug!("Changing into workspace: {}", info.name);
+                            if let Err(e) = workspace_manager.record_visit(&info.name) {
+                                warn!("Failed to record visited workspace: {}", e);
+                            }
+                            // As with list, the wrapper changes the directory unless the path
+                            // is captured with $(...) or redirected
+                            if std::io::stdout().is_terminal() {
+                                shell::write_cd_target(&info.path)
+                            } else {
+                                Ok(false)
+                            }
+                            .map(|written| {
+                                if !written {
+                                    println!("{}", info.path);
+                                }
+                            })
+                        }
+                        Ok(None) => {
+                            debug!("Picker closed without a selection");
+                            Ok(())
+                        }
+                        Err(e) => {
+                            error!("Failed to resolve workspace: {}", e);
+                            Err(e)
+                        }
+                    }
+                }
+                Commands::Exec {
+                    workspace,
+                    config,
+                    command,
+                } => {
+                    debug!("Executing command in workspace: {}", workspace);
+                    debug!("Using configuration file: {}", config);
+
+                    let config = load_config_from_path(&config);
+
+                    match workspace_manager
+                        .find_workspace(&workspace)
+                        .and_then(|info| {
+                            workspace_manager.execute_in_workspace(
+                                &info,
+                                &command,
+                                &config.workspace.env,
+                            )
+                        }) {
+                        Ok(code) => {
+                            debug!("Command exited with code: {}", code);
+                            // Propagate the exit code of the command
+                            std::process::exit(code);
+                        }
+                        Err(e) => {
+                            error!("Failed to execute command in workspace: {}", e);
+                            Err(e)
+                        }
+                    }
+                }
+                Commands::Foreach {
+                    config,
+                    filter,
+                    parallel,
+                    group,
+                    command,
+                } => {
+                    debug!("Running command in all workspaces");
+                    debug!("Using configuration file: {}", config);
+
+                    let config = load_config_from_path(&config);
+                    let options = foreach::ForeachOptions {
+                        parallel,
+                        grouped: group,
+                    };
+
+                    match workspace_manager.list_workspaces().and_then(|workspaces| {
+                        let workspaces = match &filter {
+                            Some(pattern) => workspace::filter_workspaces(workspaces, pattern),
+                            None => workspaces,
+                        };
+                        if workspaces.is_empty() {
+                            println!("No workspaces to run in.");
+                            return Ok(Vec::new());
+                        }
+                        foreach::run_foreach(&workspaces, &command, &config.workspace.env, &options)
+                    }) {
+                        Ok(results) => {
+                            if !results.is_empty() {
+                                foreach::print_summary(&results);
+                            }
+                            if results.iter().all(foreach::ForeachResult::is_success) {
+                                Ok(())
+                            } else {
+                                // Summary already reported the failures
+                                std::process::exit(1);
+                            }
+                        }
+                        Err(e) => {
+                            error!("Failed to run command in workspaces: {}", e);
+                            Err(e)
+                        }
+                    }
+                }
+                Commands::Sync {
+                    workspace,
+                    all,
+                    strategy,
+                    no_fetch,
+                    config,
+                } => {
+                    debug!("Starting workspace sync");
+                    debug!("Using configuration file: {}", config);
+
+                    let config = load_config_from_path(&config);
+                    let strategy = strategy.unwrap_or(config.workspace.sync_strategy);
+
+                    let workspaces = match workspace {
+                        Some(query) => workspace_manager.find_workspace(&query).map(|ws| vec![ws]),
+                        None if all => workspace_manager.list_workspaces(),
+                        None => unreachable!("clap requires a workspace or --all"),
+                    };
+
+                    match workspaces {
+                        Ok(workspaces) => {
+                            if !no_fetch {
+                                if let Err(e) = workspace_manager.fetch_remotes() {
+                                    // Sync onto what we already have locally
+                                    eprintln!("⚠️  {e}");
+                                }
+                            }
+
+                            let results: Vec<sync::SyncResult> = workspaces
+                                .iter()
+                                .map(|ws| match workspace_manager.sync_target(ws) {
+                                    Ok(target) => {
+                                        println!("🔀 Syncing {} onto {}", ws.name, target);
+                                        let outcome = sync::sync_workspace(ws, &target, strategy);
+                                        sync::SyncResult {
+                                            workspace: ws.name.clone(),
+                                            target,
+                                            outcome,
+                                        }
+                                    }
+                                    Err(e) => sync::SyncResult {
+                                        workspace: ws.name.clone(),
+                                        target: String::new(),
+                                        outcome: sync::SyncOutcome::Failed(e.to_string()),
+                                    },
+                                })
+                                .collect();
+
+                            sync::print_report(&results);
+                            if results.iter().any(sync::SyncResult::is_error) {
+                                // Report already shows the conflicts and failures
+                                std::process::exit(1);
+                            }
+                            Ok(())
+                        }
+                        Err(e) => {
+                            error!("Failed to resolve workspaces to sync: {}", e);
+                            Err(e)
+                        }
+                    }
+                }
+                Commands::Rename {
+                    workspace,
+                    new_name,
+                } => {
+                    debug!("Renaming workspace {} to {}", workspace, new_name);
+
+                    match workspace_manager
+                        .find_workspace(&workspace)
+                        .and_then(|info| workspace_manager.rename_workspace(&info, &new_name))
+                    {
+                        Ok(info) => {
+                            println!("✏️  Renamed workspace:");
+                            println!("  Name: {}", info.name);
+                            println!("  Path: {}", info.path);
+                            println!("  Branch: {}", info.branch);
+                            Ok(())
+                        }
+                        Err(e) => {
+                            error!("Failed to rename workspace: {}", e);
+                            Err(e)
+                        }
+                    }
+                }
+                Commands::Lock { workspace, reason } => {
+                    debug!("Locking workspace {}", workspace);
+
+                    match workspace_manager
+                        .find_workspace(&workspace)
+                        .and_then(|info| {
+                            workspace_manager
+                                .lock_workspace(&info, reason.as_deref())
+                                .map(|()| info)
+                        }) {
+                        Ok(info) => {
+                            println!("🔒 Locked workspace: {}", info.name);
+                            Ok(())
+                        }
+                        Err(e) => {
+                            error!("Failed to lock workspace: {}", e);
+                            Err(e)
+                        }
+                    }
+                }
+                Commands::Unlock { workspace } => {
+                    debug!("Unlocking workspace {}", workspace);
+
+                    match workspace_manager
+                        .find_workspace(&workspace)
+                        .and_then(|info| workspace_manager.unlock_workspace(&info).map(|()| info))
+                    {
+                        Ok(info) => {
+                            println!("🔓 Unlocked workspace: {}", info.name);
+                            Ok(())
+                        }
+                        Err(e) => {
+                            error!("Failed to unlock workspace: {}", e);
+                            Err(e)
+                        }
+                    }
+                }
+                Commands::Gc {
+                    yes,
+                    dry_run,
+                    force,
+                    max_age,
+                    max_idle,
+                    config,
+                } => {
+                    debug!("Looking for workspaces to collect");
+                    debug!("Using configuration file: {}", config);
+
+                    let config = load_config_from_path(&config);
+                    let settings = config::GcSettings {
+                        max_age_days: max_age.or(config.gc.max_age_days),
+                        max_idle_days: max_idle.or(config.gc.max_idle_days),
+                    };
+
+                    run_gc(&workspace_manager, &settings, yes, dry_run, force).inspect_err(|e| {
+                        error!("Garbage collection failed: {}", e);
+                    })
+                }
+                Commands::Doctor { yes, config } => {
+                    debug!("Checking worktrees");
+                    debug!("Using configuration file: {}", config);
+
+                    let config = load_config_from_path(&config);
+                    run_doctor(&workspace_manager, &config.workspace, yes).inspect_err(|e| {
+                        error!("Doctor failed: {}", e);
+                    })
+                }
+                Commands::Init { .. }
+                | Commands::ShellInit { .. }
+                | Commands::Completions { .. }
+                | Commands::Complete { .. } => {
                     // This case is already handled above
                     unreachable!()
                 }
@@ -154,6 +520,39 @@ fn main() {
     debug!("Exiting gitws application normally");
 }
 
+/// Output of `gitws list`, written to stdout or to the --output-file
+fn write_list_output(output_file: Option<&str>, output: &str) -> error::GitwsResult<()> {
+    use std::io::Write;
+
+    match output_file {
+        Some(path) => std::fs::write(path, output).map_err(|e| {
+            error!("Failed to write output file: {} - {}", path, e);
+            GitwsError::io(format!("Failed to write {path}: {e}"))
+        }),
+        None => {
+            let mut stdout = std::io::stdout().lock();
+            stdout
+                .write_all(output.as_bytes())
+                .and_then(|()| stdout.flush())
+                .map_err(|e| GitwsError::io(format!("Failed to write output: {e}")))
+        }
+    }
+}
+
+/// Where the TUI is drawn: the controlling terminal, or stderr without one,
+/// so that stdout only carries the selected path
+fn tui_output() -> Box<dyn std::io::Write> {
+    use std::io::BufWriter;
+
+    match std::fs::OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(tty) => Box::new(BufWriter::new(tty)),
+        Err(e) => {
+            debug!("Cannot open /dev/tty ({}), drawing the TUI on stderr", e);
+            Box::new(BufWriter::new(std::io::stderr()))
+        }
+    }
+}
+
 /// Initialize logging
 fn init_logging(is_tui_mode: bool) {
     use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -187,7 +586,213 @@ fn init_logging(is_tui_mode: bool) {
         .init();
 }
 
-fn run_tui() -> std::io::Result<Option<String>> {
+/// Remove the workspaces gc finds, after confirmation unless `yes` is set
+fn run_gc(
+    workspace_manager: &WorkspaceManager,
+    settings: &config::GcSettings,
+    yes: bool,
+    dry_run: bool,
+    force: bool,
+) -> error::GitwsResult<()> {
+    let now = chrono::Local::now();
+    let verdicts: Vec<(workspace::WorkspaceInfo, gc::GcVerdict)> = workspace_manager
+        .list_workspaces()?
+        .into_iter()
+        .map(|ws| {
+            let mut facts = gc::GcFacts::gather(workspace_manager, &ws, now);
+            if force {
+                facts.locked = None;
+            }
+            let verdict = gc::evaluate(&facts, settings);
+            (ws, verdict)
+        })
+        .collect();
+
+    if verdicts
+        .iter()
+        .all(|(_, verdict)| *verdict == gc::GcVerdict::Keep)
+    {
+        println!("✨ No workspaces to collect.");
+        return Ok(());
+    }
+    println!("🧹 Workspaces to collect:");
+    gc::print_plan(&verdicts);
+
+    let to_remove: Vec<&workspace::WorkspaceInfo> = verdicts
+        .iter()
+        .filter(|(_, verdict)| matches!(verdict, gc::GcVerdict::Remove(_)))
+        .map(|(ws, _)| ws)
+        .collect();
+    if to_remove.is_empty() || dry_run {
+        return Ok(());
+    }
+    let prompt = format!("Remove {} workspace(s)?", to_remove.len());
+    if !yes && !utils::confirm(&prompt).map_err(|e| GitwsError::io(format!("Input error: {e}")))? {
+        println!("Operation cancelled.");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for workspace in to_remove {
+        match workspace_manager.remove_workspace(&workspace.name, force) {
+            Ok(()) => println!("  ✅ Removed {}", workspace.name),
+            Err(e) => {
+                println!("  ❌ {}: {e}", workspace.name);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(GitwsError::workspace(format!(
+            "Failed to remove {failed} workspace(s)"
+        )));
+    }
+    Ok(())
+}
+
+fn run_doctor(
+    workspace_manager: &WorkspaceManager,
+    settings: &config::WorkspaceSettings,
+    yes: bool,
+) -> error::GitwsResult<()> {
+    let repo = workspace_manager.repository();
+    let problems = doctor::diagnose(repo, settings)?;
+    if problems.is_empty() {
+        println!("✅ No problems found.");
+        return Ok(());
+    }
+    println!("🩺 Findings:");
+    let issues = doctor::print_report(&problems);
+
+    let fixable: Vec<&doctor::Problem> = problems
+        .iter()
+        .filter(|problem| problem.fix_label().is_some())
+        .collect();
+    if !fixable.is_empty() {
+        let prompt = format!("Apply {} fix(es)?", fixable.len());
+        if !yes
+            && !utils::confirm(&prompt).map_err(|e| GitwsError::io(format!("Input error: {e}")))?
+        {
+            println!("Operation cancelled.");
+            return Err(GitwsError::workspace(format!("{issues} problem(s) left")));
+        }
+    }
+
+    let mut fixed = 0;
+    for problem in fixable {
+        match doctor::fix(repo, problem) {
+            Ok(()) => {
+                if let doctor::Problem::MissingDirectory { name, .. } = problem {
+                    workspace_manager.forget_workspace(name);
+                }
+                println!("  ✅ {}", problem.description());
+                fixed += 1;
+            }
+            Err(e) => println!("  ❌ {}: {e}", problem.description()),
+        }
+    }
+    if fixed < issues {
+        return Err(GitwsError::workspace(format!(
+            "{} problem(s) left",
+            issues - fixed
+        )));
+    }
+    Ok(())
+}
+
+/// Workspace for `gitws cd`: the previously visited one for `-`, the only match, or the one
+/// picked from several matches (None when the picker is closed)
+fn resolve_cd_target(
+    workspace_manager: &WorkspaceManager,
+    query: &str,
+    config: &config::WorkspaceConfig,
+) -> error::GitwsResult<Option<workspace::WorkspaceInfo>> {
+    if query == "-" {
+        return workspace_manager.previous_workspace().map(Some);
+    }
+
+    let mut matches = workspace::match_workspaces(workspace_manager.list_workspaces()?, query);
+    match matches.len() {
+        0 => Err(GitwsError::workspace(format!(
+            "No workspace matches: {query}"
+        ))),
+        1 => Ok(Some(matches.remove(0))),
+        _ if std::fs::File::open("/dev/tty").is_err() => {
+            // Nobody to ask: report the candidates like exec and sync do
+            workspace_manager.find_workspace(query).map(Some)
+        }
+        _ => run_picker(query, matches, config)
+            .map_err(|e| GitwsError::tui(format!("Picker error: {e}"))),
+    }
+}
+
+/// Compact picker for an ambiguous `gitws cd` query, drawn below the prompt
+fn run_picker(
+    query: &str,
+    workspaces: Vec<workspace::WorkspaceInfo>,
+    config: &config::WorkspaceConfig,
+) -> std::io::Result<Option<workspace::WorkspaceInfo>> {
+    use crossterm::{
+        event::{self, Event},
+        execute,
+        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    };
+    use ratatui::{backend::CrosstermBackend, Terminal, TerminalOptions, Viewport};
+    use std::io;
+    use tui::picker::{Picker, PickerAction};
+
+    let theme = tui::theme::Theme::from_config(&config.tui)
+        .map_err(|e| io::Error::other(format!("Invalid theme: {e}")))?;
+    let mut picker = Picker::new(query, workspaces);
+
+    // An inline viewport asks the terminal for the cursor position through stdout, so a
+    // captured stdout gets the alternate screen instead
+    let inline = io::stdout().is_terminal();
+    enable_raw_mode()?;
+    let mut output = tui_output();
+    let viewport = if inline {
+        Viewport::Inline(picker.height())
+    } else {
+        execute!(output, EnterAlternateScreen)?;
+        Viewport::Fullscreen
+    };
+    let mut terminal =
+        match Terminal::with_options(CrosstermBackend::new(output), TerminalOptions { viewport }) {
+            Ok(terminal) => terminal,
+            Err(e) => {
+                disable_raw_mode()?;
+                return Err(e);
+            }
+        };
+
+    let result = loop {
+        if let Err(e) = terminal.draw(|f| picker.draw(f, &theme)) {
+            break Err(e);
+        }
+        match event::read() {
+            Ok(Event::Key(key)) => match picker.handle_key(key) {
+                PickerAction::None => {}
+                PickerAction::Cancel => break Ok(None),
+                PickerAction::Select(workspace) => break Ok(Some(workspace)),
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+
+    // Cleanup: leave nothing of the picker behind
+    if inline {
+        terminal.clear()?;
+    } else {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    }
+    disable_raw_mode()?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+fn run_tui(config: &config::WorkspaceConfig) -> std::io::Result<Option<String>> {
     use crossterm::{
         event::{DisableMouseCapture, EnableMouseCapture},
         execute,
@@ -196,15 +801,23 @@ fn run_tui() -> std::io::Result<Option<String>> {
     use ratatui::{backend::CrosstermBackend, Terminal};
     use std::io;
 
+    // Keys and colours from the configuration file; mistakes are reported before taking over the terminal
+    let keymap = tui::keymap::Keymap::from_config(&config.tui.keymap)
+        .map_err(|e| io::Error::other(format!("Invalid keymap: {e}")))?;
+    let theme = tui::theme::Theme::from_config(&config.tui)
+        .map_err(|e| io::Error::other(format!("Invalid theme: {e}")))?;
+
     // Terminal setup
     enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    let mut output = tui_output();
+    execute!(output, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(output);
     let mut terminal = Terminal::new(backend)?;
 
     // App with real data
     let mut app = tui::App::new();
+    app.keymap = keymap;
+    app.theme = theme;
 
     // Load workspace data
     let workspace_manager = match WorkspaceManager::new() {
@@ -222,14 +835,62 @@ fn run_tui() -> std::io::Result<Option<String>> {
         }
     };
 
+    // Sort and grouping from the previous session
+    app.set_tui_state(workspace_manager.tui_state());
+
     if let Err(e) = app.load_workspaces(&workspace_manager) {
         // Show error but continue with empty list
         eprintln!("Workspace loading warning: {e}");
     }
 
+    // Statuses and details are loaded by background workers
+    let loader = match tui::loader::Loader::new(&workspace_manager) {
+        Ok(loader) => loader,
+        Err(e) => {
+            disable_raw_mode()?;
+            execute!(
+                terminal.backend_mut(),
+                LeaveAlternateScreen,
+                DisableMouseCapture
+            )?;
+            eprintln!("Background loader initialization error: {e}");
+            return Ok(None);
+        }
+    };
+
+    // Workspace creation started with 'n', running in the background
+    let mut creation: Option<std::sync::mpsc::Receiver<tui::create::CreateEvent>> = None;
+
     // Main loop
     let selected_path = loop {
-        terminal.draw(|f| tui::ui::draw(f, &app, &workspace_manager))?;
+        if let Some(receiver) = &creation {
+            for event in receiver.try_iter() {
+                if let tui::create::CreateEvent::Finished(Ok(workspace)) = &event {
+                    // Show and select the new workspace
+                    if let Err(e) = app.load_workspaces(&workspace_manager) {
+                        warn!("Failed to reload workspaces: {}", e);
+                    }
+                    app.select_workspace(&workspace.name);
+                }
+                app.apply_create_event(event);
+            }
+            if !app.is_creating() {
+                creation = None;
+            }
+        }
+
+        app.update_preview();
+        for request in app.take_load_requests() {
+            loader.request(request);
+        }
+        for result in loader.try_results() {
+            app.apply_load_result(result);
+        }
+        app.on_tick();
+
+        let mut click_areas = tui::app::ClickAreas::default();
+        terminal.draw(|f| click_areas = tui::ui::draw(f, &app))?;
+        app.click_areas = click_areas;
 
         match tui::events::handle_events(&mut app)? {
             tui::events::AppAction::Quit => break None,
@@ -246,23 +907,25 @@ fn run_tui() -> std::io::Result<Option<String>> {
                             }
                         }
                         Err(e) => {
-                            eprintln!("Bulk deletion error: {e}");
-                            // Update app state for all workspaces (sync display with actual state)
-                            for workspace_name in &workspace_names {
-                                app.remove_workspace(workspace_name);
+                            // Sync display with actual state: some may be gone, locked ones stay
+                            app.message = Some(format!("❌ {e}"));
+                            if let Err(e) = app.load_workspaces(&workspace_manager) {
+                                warn!("Failed to reload workspaces: {}", e);
                             }
                         }
                     }
                 } else {
                     // Single workspace deletion
                     for workspace_name in workspace_names {
-                        match workspace_manager.remove_workspace(&workspace_name) {
+                        match workspace_manager.remove_workspace(&workspace_name, false) {
                             Ok(()) => {
                                 app.remove_workspace(&workspace_name);
                             }
                             Err(e) => {
-                                eprintln!("Deletion error for {workspace_name}: {e}");
-                                app.remove_workspace(&workspace_name);
+                                app.message = Some(format!("❌ {e}"));
+                                if let Err(e) = app.load_workspaces(&workspace_manager) {
+                                    warn!("Failed to reload workspaces: {}", e);
+                                }
                             }
                         }
                     }
@@ -270,10 +933,141 @@ fn run_tui() -> std::io::Result<Option<String>> {
                 // Clear selections after any delete operation
                 app.clear_all_selections();
             }
+            tui::events::AppAction::ToggleLock(workspace_name) => {
+                let Some(workspace) = app
+                    .workspaces
+                    .iter()
+                    .find(|ws| ws.name == workspace_name)
+                    .cloned()
+                else {
+                    continue;
+                };
+                let result = if workspace.is_locked() {
+                    workspace_manager
+                        .unlock_workspace(&workspace)
+                        .map(|()| None)
+                } else {
+                    workspace_manager
+                        .lock_workspace(&workspace, None)
+                        .map(|()| Some(String::new()))
+                };
+                match result {
+                    Ok(locked) => {
+                        app.message = Some(if locked.is_some() {
+                            format!("🔒 Locked {}", workspace.name)
+                        } else {
+                            format!("🔓 Unlocked {}", workspace.name)
+                        });
+                        app.set_locked(&workspace.name, locked);
+                    }
+                    Err(e) => app.message = Some(format!("❌ {e}")),
+                }
+            }
+            tui::events::AppAction::Refresh => {
+                // Sizes are cached by a shallow fingerprint; walk every tree again
+                workspace_manager.size_cache().clear();
+                let selected = app.get_selected_workspace().map(|ws| ws.name.clone());
+                match app.load_workspaces(&workspace_manager) {
+                    Ok(()) => app.message = Some("🔄 Reloaded workspaces".to_string()),
+                    Err(e) => app.message = Some(format!("❌ {e}")),
+                }
+                if let Some(name) = selected {
+                    app.select_workspace(&name);
+                }
+            }
+            tui::events::AppAction::OpenWorkspace(target) => {
+                let Some(workspace) = app.get_selected_workspace().cloned() else {
+                    continue;
+                };
+                let Some(command) = target.command(&config.tui.open) else {
+                    app.message = Some(format!(
+                        "No command configured for tui.open.{}",
+                        target.config_key()
+                    ));
+                    continue;
+                };
+
+                let result = if command.wait {
+                    // Hand the terminal over until the program exits
+                    disable_raw_mode()?;
+                    execute!(
+                        terminal.backend_mut(),
+                        LeaveAlternateScreen,
+                        DisableMouseCapture
+                    )?;
+                    let result =
+                        tui::open::run_on_terminal(&workspace, &command, &config.workspace.env);
+                    enable_raw_mode()?;
+                    execute!(
+                        terminal.backend_mut(),
+                        EnterAlternateScreen,
+                        EnableMouseCapture
+                    )?;
+                    terminal.clear()?;
+                    result
+                } else {
+                    tui::open::spawn_open(&workspace, &command, &config.workspace.env)
+                };
+                app.message = Some(match result {
+                    Ok(()) => format!("Opened {} in {}", workspace.name, target.label()),
+                    Err(e) => format!("❌ {e}"),
+                });
+            }
+            tui::events::AppAction::RenameWorkspace {
+                workspace_name,
+                new_task_name,
+            } => {
+                let result = workspace_manager.list_workspaces().and_then(|workspaces| {
+                    let workspace = workspaces
+                        .into_iter()
+                        .find(|ws| ws.name == workspace_name)
+                        .ok_or_else(|| {
+                            GitwsError::workspace(format!("Workspace not found: {workspace_name}"))
+                        })?;
+                    workspace_manager.rename_workspace(&workspace, &new_task_name)
+                });
+                match result {
+                    Ok(renamed) => {
+                        app.hide_rename_form();
+                        if let Err(e) = app.load_workspaces(&workspace_manager) {
+                            warn!("Failed to reload workspaces: {}", e);
+                        }
+                        app.select_workspace(&renamed.name);
+                    }
+                    Err(e) => app.set_rename_error(e.to_string()),
+                }
+            }
+            tui::events::AppAction::CreateWorkspace {
+                task_name,
+                base_ref,
+            } => {
+                match tui::create::spawn_create(
+                    &workspace_manager,
+                    config.workspace.clone(),
+                    task_name,
+                    base_ref,
+                ) {
+                    Ok(receiver) => creation = Some(receiver),
+                    Err(e) => app
+                        .apply_create_event(tui::create::CreateEvent::Finished(Err(e.to_string()))),
+                }
+            }
             tui::events::AppAction::None => {}
         }
     };
 
+    if let Err(e) = workspace_manager.save_tui_state(app.tui_state()) {
+        warn!("Failed to save TUI settings: {}", e);
+    }
+    if let Some(workspace) = selected_path
+        .as_ref()
+        .and_then(|path| app.workspaces.iter().find(|ws| &ws.path == path))
+    {
+        if let Err(e) = workspace_manager.record_visit(&workspace.name) {
+            warn!("Failed to record visited workspace: {}", e);
+        }
+    }
+
     // Cleanup
     disable_raw_mode()?;
     execute!(
@@ -0,0 +1,156 @@
+use crate::error::{GitwsError, GitwsResult};
+use std::fs;
+use tracing::{debug, error};
+
+/// Environment variable naming the file the shell wrapper reads the directory to `cd` into from
+pub const CD_FILE_ENV: &str = "GITWS_CD_FILE";
+
+/// Shells supported by `gitws shell-init`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Hand a directory to the shell wrapper, which changes into it after gitws exits.
+/// Returns false when gitws is not running under the wrapper.
+pub fn write_cd_target(path: &str) -> GitwsResult<bool> {
+    let Some(cd_file) = std::env::var_os(CD_FILE_ENV).filter(|file| !file.is_empty()) else {
+        return Ok(false);
+    };
+    debug!("Writing cd target to {:?}: {}", cd_file, path);
+    fs::write(&cd_file, path).map_err(|e| {
+        error!("Failed to write cd target: {:?} - {}", cd_file, e);
+        GitwsError::io(format!(
+            "Failed to write {}: {e}",
+            cd_file.to_string_lossy()
+        ))
+    })?;
+    Ok(true)
+}
+
+/// Shell code that wraps the gitws binary in a `gitws` function, optionally with an Alt-W widget
+pub fn init_script(shell: Shell, key_bindings: bool) -> String {
+    let (wrapper, bindings) = match shell {
+        Shell::Bash => (POSIX_WRAPPER, BASH_BINDINGS),
+        Shell::Zsh => (POSIX_WRAPPER, ZSH_BINDINGS),
+        Shell::Fish => (FISH_WRAPPER, FISH_BINDINGS),
+    };
+    let mut script = wrapper.to_string();
+    if key_bindings {
+        script.push('\n');
+        script.push_str(bindings);
+    }
+    script
+}
+
+// The wrapper passes a temporary file through GITWS_CD_FILE; `gitws start --cd`, and `gitws list`
+// and `gitws cd` when their output goes to the terminal, write the directory there instead of
+// printing it.
+const POSIX_WRAPPER: &str = r#"# gitws shell integration
+gitws() {
+    local __gitws_cd_file __gitws_status __gitws_target
+    __gitws_cd_file="$(mktemp "${TMPDIR:-/tmp}/gitws-cd.XXXXXX")" || return 1
+    GITWS_CD_FILE="$__gitws_cd_file" command gitws "$@"
+    __gitws_status=$?
+    __gitws_target="$(cat "$__gitws_cd_file")"
+    rm -f "$__gitws_cd_file"
+    if [ -n "$__gitws_target" ] && [ -d "$__gitws_target" ]; then
+        cd -- "$__gitws_target" || return
+    fi
+    return "$__gitws_status"
+}
+"#;
+
+const BASH_BINDINGS: &str = r#"# Alt-W: pick a workspace in the TUI and cd into it
+__gitws_widget() {
+    gitws list
+}
+if [[ $- == *i* ]]; then
+    bind -x '"\ew": __gitws_widget'
+fi
+"#;
+
+const ZSH_BINDINGS: &str = r#"# Alt-W: pick a workspace in the TUI and cd into it
+__gitws_widget() {
+    gitws list </dev/tty
+    zle reset-prompt
+}
+zle -N __gitws_widget
+bindkey '\ew' __gitws_widget
+"#;
+
+const FISH_WRAPPER: &str = r#"# gitws shell integration
+function gitws
+    set -l tmpdir /tmp
+    set -q TMPDIR; and set tmpdir $TMPDIR
+    set -l cd_file (mktemp "$tmpdir/gitws-cd.XXXXXX"); or return 1
+    GITWS_CD_FILE=$cd_file command gitws $argv
+    set -l gitws_status $status
+    set -l target (cat $cd_file)
+    rm -f $cd_file
+    if test -n "$target" -a -d "$target"
+        cd $target
+    end
+    return $gitws_status
+end
+"#;
+
+const FISH_BINDINGS: &str = r#"# Alt-W: pick a workspace in the TUI and cd into it
+function __gitws_widget
+    gitws list
+    commandline -f repaint
+end
+bind \ew __gitws_widget
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init_script() {
+        for shell in [Shell::Bash, Shell::Zsh] {
+            let script = init_script(shell, true);
+            assert!(script.contains("gitws() {"));
+            assert!(script.contains("GITWS_CD_FILE="));
+            assert!(script.contains("__gitws_widget"));
+        }
+        assert!(init_script(Shell::Bash, true).contains("bind -x"));
+        assert!(init_script(Shell::Zsh, true).contains("bindkey"));
+
+        let fish = init_script(Shell::Fish, true);
+        assert!(fish.contains("function gitws"));
+        assert!(fish.contains("bind \\ew __gitws_widget"));
+
+        assert!(!init_script(Shell::Zsh, false).contains("__gitws_widget"));
+    }
+
+    #[test]
+    fn test_init_script_syntax() {
+        // Parse the scripts with the shells that are installed
+        for (shell, program, args) in [
+            (Shell::Bash, "bash", &["-n"][..]),
+            (Shell::Zsh, "zsh", &["-n"][..]),
+            (Shell::Fish, "fish", &["--no-execute"][..]),
+        ] {
+            let script = init_script(shell, true);
+            let path = std::env::temp_dir().join(format!("gitws-shell-init-{program}"));
+            fs::write(&path, script).unwrap();
+            match std::process::Command::new(program)
+                .args(args)
+                .arg(&path)
+                .output()
+            {
+                Ok(output) => assert!(
+                    output.status.success(),
+                    "{program}: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+                Err(_) => eprintln!("{program} not installed, skipping"),
+            }
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
@@ -0,0 +1,384 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::SystemTime;
+use tracing::debug;
+
+/// Number of subdirectories kept in the breakdown
+const LARGEST_DIRS: usize = 5;
+
+/// Size of a directory tree (`.git` directories are skipped)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DirectorySize {
+    /// Sum of file lengths
+    pub apparent_bytes: u64,
+    /// Space allocated on disk; hard-linked files are counted once
+    pub disk_bytes: u64,
+    /// Largest direct subdirectories by disk usage, largest first
+    pub largest_dirs: Vec<(String, u64)>,
+}
+
+/// Walk a directory tree and compute its size
+///
+/// Entries that cannot be read are skipped, so the result may be smaller
+/// than the real usage; only an unreadable root is an error.
+pub fn compute_directory_size(path: &Path) -> io::Result<DirectorySize> {
+    debug!("Computing directory size: {}", path.display());
+
+    let mut seen_inodes = HashSet::new();
+    let mut total = DirectorySize::default();
+    let mut subdirs = Vec::new();
+
+    for entry in fs::read_dir(path)? {
+        let Some((entry, metadata)) = readable_entry(entry) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if entry.file_name() == ".git" {
+                continue;
+            }
+            let (apparent, disk) = walk(&entry.path(), &mut seen_inodes);
+            total.apparent_bytes += apparent;
+            total.disk_bytes += disk;
+            subdirs.push((entry.file_name().to_string_lossy().to_string(), disk));
+        } else if let Some((apparent, disk)) = file_usage(&metadata, &mut seen_inodes) {
+            total.apparent_bytes += apparent;
+            total.disk_bytes += disk;
+        }
+    }
+
+    subdirs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    subdirs.truncate(LARGEST_DIRS);
+    total.largest_dirs = subdirs;
+    Ok(total)
+}
+
+/// Returns (apparent bytes, disk bytes) of a subtree
+fn walk(path: &Path, seen_inodes: &mut HashSet<(u64, u64)>) -> (u64, u64) {
+    let mut apparent = 0;
+    let mut disk = 0;
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            debug!("Skipping unreadable directory {}: {}", path.display(), e);
+            return (0, 0);
+        }
+    };
+    for entry in entries {
+        let Some((entry, metadata)) = readable_entry(entry) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if entry.file_name() == ".git" {
+                continue;
+            }
+            let (sub_apparent, sub_disk) = walk(&entry.path(), seen_inodes);
+            apparent += sub_apparent;
+            disk += sub_disk;
+        } else if let Some((file_apparent, file_disk)) = file_usage(&metadata, seen_inodes) {
+            apparent += file_apparent;
+            disk += file_disk;
+        }
+    }
+
+    (apparent, disk)
+}
+
+/// Directory entry with its metadata, or None if it cannot be read
+fn readable_entry(entry: io::Result<fs::DirEntry>) -> Option<(fs::DirEntry, fs::Metadata)> {
+    let entry = match entry {
+        Ok(entry) => entry,
+        Err(e) => {
+            debug!("Skipping unreadable directory entry: {}", e);
+            return None;
+        }
+    };
+    // Symlinks are not followed
+    match entry.metadata() {
+        Ok(metadata) => Some((entry, metadata)),
+        Err(e) => {
+            debug!("Skipping {}: {}", entry.path().display(), e);
+            None
+        }
+    }
+}
+
+/// Size of a single file, or None if it is a hard link that was already counted
+#[cfg(unix)]
+fn file_usage(
+    metadata: &fs::Metadata,
+    seen_inodes: &mut HashSet<(u64, u64)>,
+) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    if metadata.nlink() > 1 && !seen_inodes.insert((metadata.dev(), metadata.ino())) {
+        return None;
+    }
+    // st_blocks is always in 512-byte units
+    Some((metadata.len(), metadata.blocks() * 512))
+}
+
+#[cfg(not(unix))]
+fn file_usage(
+    metadata: &fs::Metadata,
+    _seen_inodes: &mut HashSet<(u64, u64)>,
+) -> Option<(u64, u64)> {
+    Some((metadata.len(), metadata.len()))
+}
+
+/// Human readable byte count
+pub fn format_size(bytes: u64) -> String {
+    const KB: f64 = 1024.0;
+    const MB: f64 = KB * 1024.0;
+    const GB: f64 = MB * 1024.0;
+
+    let size = bytes as f64;
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else if size < MB {
+        format!("{:.1} KB", size / KB)
+    } else if size < GB {
+        format!("{:.1} MB", size / MB)
+    } else {
+        format!("{:.1} GB", size / GB)
+    }
+}
+
+/// State of a size computation
+#[derive(Debug, Clone, PartialEq)]
+pub enum SizeState {
+    Calculating,
+    Ready(DirectorySize),
+    Failed(String),
+}
+
+struct CacheEntry {
+    fingerprint: Option<SystemTime>,
+    state: SizeState,
+}
+
+/// Directory sizes computed in background threads
+///
+/// A cached size is reused until the modification time of the directory
+/// or one of its direct entries changes. Changes deeper in the tree (such as
+/// a file growing inside `target/`) are not noticed; `clear` drops every
+/// cached size so the next request walks the tree again.
+#[derive(Clone, Default)]
+pub struct SizeCache {
+    entries: Arc<Mutex<HashMap<PathBuf, CacheEntry>>>,
+}
+
+impl SizeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cached size of a directory; starts a background computation if it is missing or stale
+    pub fn get(&self, path: &Path) -> SizeState {
+        let fingerprint = fingerprint(path);
+
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(entry) = entries.get(path) {
+            match &entry.state {
+                SizeState::Calculating => return SizeState::Calculating,
+                state if entry.fingerprint == fingerprint => return state.clone(),
+                _ => debug!("Directory size is stale: {}", path.display()),
+            }
+        }
+        entries.insert(
+            path.to_path_buf(),
+            CacheEntry {
+                fingerprint,
+                state: SizeState::Calculating,
+            },
+        );
+        drop(entries);
+
+        let entries = Arc::clone(&self.entries);
+        let path = path.to_path_buf();
+        thread::spawn(move || {
+            let state = match compute_directory_size(&path) {
+                Ok(size) => SizeState::Ready(size),
+                Err(e) => SizeState::Failed(e.to_string()),
+            };
+            if let Some(entry) = entries.lock().unwrap().get_mut(&path) {
+                entry.state = state;
+            }
+        });
+
+        SizeState::Calculating
+    }
+
+    /// Forget all cached sizes
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Current state of a directory size, without starting a computation
+    pub fn peek(&self, path: &Path) -> Option<SizeState> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(path).map(|entry| entry.state.clone())
+    }
+}
+
+/// Latest modification time of a directory and its direct entries
+fn fingerprint(path: &Path) -> Option<SystemTime> {
+    let mut latest = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+                latest = latest.max(modified);
+            }
+        }
+    }
+    Some(latest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    fn wait_for_size(cache: &SizeCache, path: &Path) -> SizeState {
+        let started = Instant::now();
+        loop {
+            let state = cache.get(path);
+            if state != SizeState::Calculating || started.elapsed() > Duration::from_secs(10) {
+                return state;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_compute_directory_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("big/nested")).unwrap();
+        fs::create_dir_all(root.join("small")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("top.txt"), vec![b'a'; 100]).unwrap();
+        fs::write(root.join("big/nested/file"), vec![b'b'; 10_000]).unwrap();
+        fs::write(root.join("small/file"), vec![b'c'; 10]).unwrap();
+        fs::write(root.join(".git/objects"), vec![b'd'; 50_000]).unwrap();
+
+        let size = compute_directory_size(root).unwrap();
+        // .git is skipped
+        assert_eq!(size.apparent_bytes, 10_110);
+        assert!(size.disk_bytes > 0);
+
+        let names: Vec<&str> = size.largest_dirs.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["big", "small"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_compute_directory_size_counts_hard_links_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::create_dir_all(root.join("b")).unwrap();
+        fs::write(root.join("a/file"), vec![b'x'; 8192]).unwrap();
+
+        let single = compute_directory_size(root).unwrap();
+        fs::hard_link(root.join("a/file"), root.join("b/link")).unwrap();
+        let linked = compute_directory_size(root).unwrap();
+
+        assert_eq!(linked.apparent_bytes, single.apparent_bytes);
+        assert_eq!(linked.disk_bytes, single.disk_bytes);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_compute_directory_size_skips_unreadable_directories() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("locked")).unwrap();
+        fs::write(root.join("locked/file"), vec![b'a'; 100]).unwrap();
+        fs::write(root.join("top.txt"), vec![b'b'; 10]).unwrap();
+        fs::set_permissions(root.join("locked"), fs::Permissions::from_mode(0o000)).unwrap();
+        if fs::read_dir(root.join("locked")).is_ok() {
+            // Permissions are not enforced (running as root)
+            fs::set_permissions(root.join("locked"), fs::Permissions::from_mode(0o755)).unwrap();
+            return;
+        }
+
+        let size = compute_directory_size(root);
+        fs::set_permissions(root.join("locked"), fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(size.unwrap().apparent_bytes, 10);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
+    #[test]
+    fn test_size_cache_computes_in_background_and_invalidates() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("file"), vec![b'a'; 100]).unwrap();
+
+        let cache = SizeCache::new();
+        assert_eq!(cache.get(root), SizeState::Calculating);
+        let SizeState::Ready(size) = wait_for_size(&cache, root) else {
+            panic!("size was not computed");
+        };
+        assert_eq!(size.apparent_bytes, 100);
+
+        // Unchanged directory is served from the cache
+        assert_eq!(cache.get(root), SizeState::Ready(size));
+
+        // A newer modification time invalidates the cached size
+        fs::write(root.join("other"), vec![b'b'; 50]).unwrap();
+        fs::File::open(root.join("other"))
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        let SizeState::Ready(size) = wait_for_size(&cache, root) else {
+            panic!("size was not recomputed");
+        };
+        assert_eq!(size.apparent_bytes, 150);
+    }
+
+    #[test]
+    fn test_size_cache_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("deep")).unwrap();
+        fs::write(root.join("deep/file"), vec![b'a'; 100]).unwrap();
+
+        let cache = SizeCache::new();
+        let SizeState::Ready(size) = wait_for_size(&cache, root) else {
+            panic!("size was not computed");
+        };
+        assert_eq!(size.apparent_bytes, 100);
+
+        // Growing a nested file keeps the fingerprint of the root unchanged
+        let modified = fs::metadata(root.join("deep")).unwrap().modified().unwrap();
+        fs::write(root.join("deep/file"), vec![b'a'; 300]).unwrap();
+        fs::File::open(root.join("deep"))
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        assert_eq!(cache.get(root), SizeState::Ready(size));
+
+        cache.clear();
+        assert_eq!(cache.peek(root), None);
+        let SizeState::Ready(size) = wait_for_size(&cache, root) else {
+            panic!("size was not recomputed");
+        };
+        assert_eq!(size.apparent_bytes, 300);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::error::{GitwsError, GitwsResult};
+use crate::tui::sort::{GroupMode, SortMode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tracing::{debug, error, warn};
+
+/// Metadata recorded for a workspace when it is created
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceMetadata {
+    /// Branch (or commit) the workspace was created from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_ref: Option<String>,
+    /// Commit the workspace branch started at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_commit: Option<String>,
+}
+
+/// TUI list settings remembered between sessions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TuiState {
+    #[serde(default)]
+    pub sort: SortMode,
+    #[serde(default)]
+    pub group: GroupMode,
+}
+
+/// Workspaces changed into with `gitws cd`, `gitws start --cd` or the TUI, for `gitws cd -`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VisitState {
+    /// Workspace visited most recently
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+    /// Workspace visited before it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<String>,
+}
+
+impl VisitState {
+    /// Record a visit; visiting the current workspace again keeps the previous one
+    pub fn visit(&mut self, workspace_name: &str) {
+        if self.current.as_deref() != Some(workspace_name) {
+            self.previous = self.current.replace(workspace_name.to_string());
+        }
+    }
+
+    /// Follow a renamed workspace
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        for name in [&mut self.current, &mut self.previous]
+            .into_iter()
+            .flatten()
+        {
+            if name == old_name {
+                *name = new_name.to_string();
+            }
+        }
+    }
+}
+
+/// State gitws keeps between runs, shared by all worktrees of a repository
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GitwsState {
+    #[serde(default)]
+    pub workspaces: BTreeMap<String, WorkspaceMetadata>,
+    #[serde(default)]
+    pub tui: TuiState,
+    #[serde(default)]
+    pub visits: VisitState,
+}
+
+/// Load state file and return empty state if it is missing or unreadable
+pub fn load_state(path: &Path) -> GitwsState {
+    debug!("Loading state file: {}", path.display());
+
+    if !path.exists() {
+        debug!("State file does not exist: {}", path.display());
+        return GitwsState::default();
+    }
+
+    match fs::read_to_string(path) {
+        Ok(content) => match serde_yaml::from_str::<GitwsState>(&content) {
+            Ok(state) => state,
+            Err(e) => {
+                warn!("Failed to parse state file: {} - {}", path.display(), e);
+                GitwsState::default()
+            }
+        },
+        Err(e) => {
+            warn!("Failed to read state file: {} - {}", path.display(), e);
+            GitwsState::default()
+        }
+    }
+}
+
+/// Save state file, creating its directory if needed
+pub fn save_state(path: &Path, state: &GitwsState) -> GitwsResult<()> {
+    debug!("Saving state file: {}", path.display());
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            error!("Failed to create directory: {} - {}", parent.display(), e);
+            GitwsError::io(format!("Directory creation error: {e}"))
+        })?;
+    }
+
+    let content = serde_yaml::to_string(state)?;
+    fs::write(path, content).map_err(|e| {
+        error!("Failed to write state file: {} - {}", path.display(), e);
+        GitwsError::io(format!("Failed to write state file: {e}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_state_nonexistent_file() {
+        let state = load_state(Path::new("/path/that/does/not/exist/state.yml"));
+        assert!(state.workspaces.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_state() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("gitws/state.yml");
+
+        let mut state = GitwsState::default();
+        state.workspaces.insert(
+            "20250625-120000-feature".to_string(),
+            WorkspaceMetadata {
+                base_ref: Some("main".to_string()),
+                base_commit: None,
+            },
+        );
+        save_state(&path, &state).unwrap();
+
+        let loaded = load_state(&path);
+        assert_eq!(
+            loaded.workspaces["20250625-120000-feature"]
+                .base_ref
+                .as_deref(),
+            Some("main")
+        );
+    }
+
+    #[test]
+    fn test_tui_state_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.yml");
+
+        // State files written before TUI settings existed still load
+        fs::write(&path, "workspaces: {}\n").unwrap();
+        assert_eq!(load_state(&path).tui, TuiState::default());
+
+        let state = GitwsState {
+            tui: TuiState {
+                sort: SortMode::LastActivity,
+                group: GroupMode::BranchPrefix,
+            },
+            ..Default::default()
+        };
+        save_state(&path, &state).unwrap();
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("sort: last-activity"));
+        assert_eq!(load_state(&path).tui, state.tui);
+    }
+
+    #[test]
+    fn test_visit_state() {
+        let mut visits = VisitState::default();
+        visits.visit("a");
+        assert_eq!(visits.previous, None);
+        visits.visit("b");
+        visits.visit("b");
+        assert_eq!(visits.current.as_deref(), Some("b"));
+        assert_eq!(visits.previous.as_deref(), Some("a"));
+
+        // Going back swaps the two, so `gitws cd -` toggles like `cd -`
+        visits.visit("a");
+        assert_eq!(visits.previous.as_deref(), Some("b"));
+
+        visits.rename("b", "c");
+        assert_eq!(visits.previous.as_deref(), Some("c"));
+        assert_eq!(visits.current.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_load_state_invalid_yaml() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.yml");
+        fs::write(&path, "workspaces: [").unwrap();
+
+        // Broken state is ignored instead of failing
+        let state = load_state(&path);
+        assert!(state.workspaces.is_empty());
+    }
+}
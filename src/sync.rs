@@ -0,0 +1,332 @@
+use crate::config::SyncStrategy;
+use crate::workspace::{operation_in_progress, WorkspaceInfo};
+use git2::{Repository, StatusOptions};
+use std::path::Path;
+use std::process::Command;
+use tracing::{debug, error};
+
+/// What happened when syncing one workspace
+#[derive(Debug, PartialEq)]
+pub enum SyncOutcome {
+    /// Branch was rebased onto / merged with the target
+    Updated,
+    /// Branch already contains the target
+    UpToDate,
+    /// Workspace was left untouched (dirty, operation in progress, detached HEAD)
+    Skipped(String),
+    /// Rebase/merge hit conflicts and was aborted; holds the conflicted paths
+    Conflict(Vec<String>),
+    Failed(String),
+}
+
+#[derive(Debug)]
+pub struct SyncResult {
+    pub workspace: String,
+    pub target: String,
+    pub outcome: SyncOutcome,
+}
+
+impl SyncResult {
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self.outcome,
+            SyncOutcome::Conflict(_) | SyncOutcome::Failed(_)
+        )
+    }
+}
+
+/// Rebase or merge a workspace branch onto `target`
+///
+/// Dirty worktrees and worktrees with an operation already in progress are skipped.
+/// On conflict the rebase/merge is aborted so the worktree is left as it was.
+pub fn sync_workspace(
+    workspace: &WorkspaceInfo,
+    target: &str,
+    strategy: SyncStrategy,
+) -> SyncOutcome {
+    debug!(
+        "Syncing workspace {} onto {} ({:?})",
+        workspace.name, target, strategy
+    );
+
+    let repo = match Repository::open(&workspace.path) {
+        Ok(repo) => repo,
+        Err(e) => return SyncOutcome::Failed(format!("Cannot open repository: {e}")),
+    };
+
+    if let Some(operation) = operation_in_progress(&repo) {
+        return SyncOutcome::Skipped(format!("{operation} in progress"));
+    }
+
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) => return SyncOutcome::Failed(format!("HEAD retrieval error: {e}")),
+    };
+    if !head.is_branch() {
+        return SyncOutcome::Skipped("detached HEAD".to_string());
+    }
+
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(false)
+        .include_ignored(false);
+    match repo.statuses(Some(&mut status_options)) {
+        Ok(statuses) if !statuses.is_empty() => {
+            return SyncOutcome::Skipped("uncommitted changes".to_string())
+        }
+        Ok(_) => {}
+        Err(e) => return SyncOutcome::Failed(format!("Status retrieval error: {e}")),
+    }
+
+    let target_oid = match repo
+        .revparse_single(target)
+        .and_then(|object| object.peel_to_commit())
+    {
+        Ok(commit) => commit.id(),
+        Err(e) => return SyncOutcome::Failed(format!("Target {target} not found: {e}")),
+    };
+    if let Some(head_oid) = head.target() {
+        if head_oid == target_oid
+            || repo
+                .graph_descendant_of(head_oid, target_oid)
+                .unwrap_or(false)
+        {
+            return SyncOutcome::UpToDate;
+        }
+    }
+
+    let args: Vec<&str> = match strategy {
+        SyncStrategy::Rebase => vec!["rebase", target],
+        SyncStrategy::Merge => vec!["merge", "--no-edit", target],
+    };
+    let output = match Command::new("git")
+        .args(&args)
+        .current_dir(&workspace.path)
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            error!("Failed to execute git {}: {}", args[0], e);
+            return SyncOutcome::Failed(format!("git {} execution error: {e}", args[0]));
+        }
+    };
+
+    if output.status.success() {
+        return SyncOutcome::Updated;
+    }
+
+    // Collect conflicts before aborting, then restore the worktree
+    let conflicts = conflicted_paths(Path::new(&workspace.path));
+    let _ = Command::new("git")
+        .args([args[0], "--abort"])
+        .current_dir(&workspace.path)
+        .output();
+
+    if conflicts.is_empty() {
+        SyncOutcome::Failed(format!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    } else {
+        SyncOutcome::Conflict(conflicts)
+    }
+}
+
+fn conflicted_paths(workspace_path: &Path) -> Vec<String> {
+    // Reopen so the index written by the git command is read
+    let Ok(repo) = Repository::open(workspace_path) else {
+        return Vec::new();
+    };
+    let Ok(index) = repo.index() else {
+        return Vec::new();
+    };
+    let Ok(conflicts) = index.conflicts() else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<String> = conflicts
+        .flatten()
+        .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect();
+    paths.dedup();
+    paths
+}
+
+/// Print the sync report
+pub fn print_report(results: &[SyncResult]) {
+    println!("\n📋 Sync report:");
+    for result in results {
+        match &result.outcome {
+            SyncOutcome::Updated => {
+                println!("  ✅ {}: updated onto {}", result.workspace, result.target)
+            }
+            SyncOutcome::UpToDate => println!("  ✅ {}: up to date", result.workspace),
+            SyncOutcome::Skipped(reason) => {
+                println!("  ⏭️  {}: skipped ({reason})", result.workspace)
+            }
+            SyncOutcome::Conflict(paths) => {
+                println!(
+                    "  ❌ {}: conflict with {} (aborted)",
+                    result.workspace, result.target
+                );
+                for path in paths {
+                    println!("       {path}");
+                }
+            }
+            SyncOutcome::Failed(message) => {
+                println!("  ❌ {}: {message}", result.workspace)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn commit_file(dir: &Path, file: &str, content: &str, message: &str) {
+        fs::write(dir.join(file), content).unwrap();
+        git(dir, &["add", file]);
+        git(dir, &["commit", "-q", "-m", message]);
+    }
+
+    /// Repository with a "main" branch and a "feature" worktree branched from it
+    fn setup_repo(temp_dir: &TempDir) -> (std::path::PathBuf, WorkspaceInfo) {
+        let main_path = temp_dir.path().join("main");
+        fs::create_dir_all(&main_path).unwrap();
+        git(&main_path, &["init", "-q", "-b", "main"]);
+        git(&main_path, &["config", "user.name", "test"]);
+        git(&main_path, &["config", "user.email", "test@example.com"]);
+        commit_file(&main_path, "README.md", "base\n", "initial");
+
+        let ws_path = temp_dir.path().join("feature");
+        git(
+            &main_path,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "feature",
+                ws_path.to_str().unwrap(),
+            ],
+        );
+        let workspace = WorkspaceInfo {
+            name: "feature".to_string(),
+            path: ws_path.to_str().unwrap().to_string(),
+            branch: "feature".to_string(),
+            locked: None,
+        };
+        (main_path, workspace)
+    }
+
+    #[test]
+    fn test_sync_up_to_date() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_main_path, workspace) = setup_repo(&temp_dir);
+
+        let outcome = sync_workspace(&workspace, "main", SyncStrategy::Rebase);
+        assert_eq!(outcome, SyncOutcome::UpToDate);
+    }
+
+    #[test]
+    fn test_sync_rebase_and_merge() {
+        for strategy in [SyncStrategy::Rebase, SyncStrategy::Merge] {
+            let temp_dir = TempDir::new().unwrap();
+            let (main_path, workspace) = setup_repo(&temp_dir);
+            let ws_path = Path::new(&workspace.path);
+            commit_file(ws_path, "feature.txt", "feature\n", "feature work");
+            commit_file(&main_path, "main.txt", "main\n", "main work");
+
+            let outcome = sync_workspace(&workspace, "main", strategy);
+            assert_eq!(outcome, SyncOutcome::Updated, "strategy: {strategy:?}");
+
+            // Both changes are present in the workspace
+            assert!(ws_path.join("main.txt").exists());
+            assert!(ws_path.join("feature.txt").exists());
+            assert_eq!(
+                sync_workspace(&workspace, "main", strategy),
+                SyncOutcome::UpToDate
+            );
+        }
+    }
+
+    #[test]
+    fn test_sync_conflict_is_aborted() {
+        let temp_dir = TempDir::new().unwrap();
+        let (main_path, workspace) = setup_repo(&temp_dir);
+        let ws_path = Path::new(&workspace.path);
+        commit_file(ws_path, "README.md", "feature\n", "feature change");
+        commit_file(&main_path, "README.md", "main\n", "main change");
+
+        let outcome = sync_workspace(&workspace, "main", SyncStrategy::Rebase);
+        assert_eq!(
+            outcome,
+            SyncOutcome::Conflict(vec!["README.md".to_string()])
+        );
+
+        // Rebase was aborted and the workspace is untouched
+        let repo = Repository::open(ws_path).unwrap();
+        assert!(operation_in_progress(&repo).is_none());
+        assert_eq!(
+            fs::read_to_string(ws_path.join("README.md")).unwrap(),
+            "feature\n"
+        );
+    }
+
+    #[test]
+    fn test_sync_skips_dirty_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        let (main_path, workspace) = setup_repo(&temp_dir);
+        commit_file(&main_path, "main.txt", "main\n", "main work");
+        fs::write(Path::new(&workspace.path).join("README.md"), "dirty\n").unwrap();
+
+        let outcome = sync_workspace(&workspace, "main", SyncStrategy::Rebase);
+        assert_eq!(
+            outcome,
+            SyncOutcome::Skipped("uncommitted changes".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sync_skips_operation_in_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let (main_path, workspace) = setup_repo(&temp_dir);
+        let ws_path = Path::new(&workspace.path);
+        commit_file(ws_path, "README.md", "feature\n", "feature change");
+        commit_file(&main_path, "README.md", "main\n", "main change");
+
+        // Leave a conflicted rebase behind
+        let status = Command::new("git")
+            .args(["rebase", "main"])
+            .current_dir(ws_path)
+            .output()
+            .unwrap()
+            .status;
+        assert!(!status.success());
+
+        let outcome = sync_workspace(&workspace, "main", SyncStrategy::Rebase);
+        assert_eq!(
+            outcome,
+            SyncOutcome::Skipped("rebase in progress".to_string())
+        );
+    }
+}
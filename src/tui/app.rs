@@ -1,7 +1,116 @@
+use crate::diff::{DiffTarget, WorkspaceDiff};
 use crate::error::GitwsResult;
-use crate::workspace::{WorkspaceInfo, WorkspaceManager};
+use crate::fuzzy::fuzzy_match;
+use crate::size::{DirectorySize, SizeState};
+use crate::state::TuiState;
+use crate::tui::create::CreateEvent;
+use crate::tui::keymap::{KeyPress, Keymap};
+use crate::tui::loader::{LoadRequest, LoadResult, LoadState, WorkspaceSummary};
+use crate::tui::sort::{GroupMode, SortMode};
+use crate::tui::theme::Theme;
+use crate::workspace::{WorkspaceDetails, WorkspaceInfo, WorkspaceManager};
+use ratatui::layout::{Position, Rect};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Instant;
 use tracing::debug;
 
+/// Characters of a row that matched the filter, for highlighting
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterMatch {
+    pub branch: Vec<usize>,
+    pub path: Vec<usize>,
+}
+
+/// State of the diff viewer
+#[derive(Debug)]
+pub struct DiffView {
+    pub workspace: WorkspaceInfo,
+    pub target: DiffTarget,
+    pub diff: LoadState<WorkspaceDiff>,
+    pub selected_file: usize,
+    pub scroll: u16,
+}
+
+/// Field of the create form that receives typed characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreateField {
+    TaskName,
+    BaseRef,
+}
+
+/// Input form for a new workspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateForm {
+    pub task_name: String,
+    /// Empty means the current HEAD
+    pub base_ref: String,
+    pub focus: CreateField,
+    pub error: Option<String>,
+}
+
+/// Workspace creation running in the background
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateProgress {
+    pub task_name: String,
+    pub lines: Vec<String>,
+    /// Lines scrolled back from the latest output (0 follows new output)
+    pub scroll_back: usize,
+    /// Name of the created workspace or the error, once finished
+    pub result: Option<Result<String, String>>,
+}
+
+/// Input form for a new task name of a workspace
+#[derive(Debug, Clone)]
+pub struct RenameForm {
+    pub workspace: WorkspaceInfo,
+    pub new_name: String,
+    pub error: Option<String>,
+}
+
+/// Button of a confirmation dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogButton {
+    Confirm,
+    Cancel,
+}
+
+/// Clickable regions of the last drawn frame, for mouse handling
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClickAreas {
+    pub rows: Vec<(Rect, usize)>,       // Workspace rows by workspace index
+    pub checkboxes: Vec<(Rect, usize)>, // Selection checkboxes of the rows
+    pub diff_files: Vec<(Rect, usize)>, // File list of the diff viewer
+    pub buttons: Vec<(Rect, DialogButton)>,
+}
+
+impl ClickAreas {
+    pub fn row_at(&self, position: Position) -> Option<usize> {
+        find_area(&self.rows, position)
+    }
+
+    pub fn checkbox_at(&self, position: Position) -> Option<usize> {
+        find_area(&self.checkboxes, position)
+    }
+
+    pub fn diff_file_at(&self, position: Position) -> Option<usize> {
+        find_area(&self.diff_files, position)
+    }
+
+    pub fn button_at(&self, position: Position) -> Option<DialogButton> {
+        find_area(&self.buttons, position)
+    }
+}
+
+fn find_area<T: Copy>(areas: &[(Rect, T)], position: Position) -> Option<T> {
+    areas
+        .iter()
+        .find(|(area, _)| area.contains(position))
+        .map(|(_, value)| *value)
+}
+
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
 pub struct App {
     pub should_quit: bool,
     pub workspaces: Vec<WorkspaceInfo>,
@@ -9,6 +118,29 @@ pub struct App {
     pub show_delete_dialog: bool,
     pub show_details_dialog: bool,
     pub selected_workspaces: Vec<bool>, // Multi-selection state for each workspace
+    // Background-loaded data by workspace name
+    pub summaries: HashMap<String, LoadState<WorkspaceSummary>>,
+    pub details: HashMap<String, LoadState<WorkspaceDetails>>,
+    pub sizes: HashMap<String, LoadState<DirectorySize>>,
+    pub pending_loads: Vec<LoadRequest>, // Requests not yet handed to the loader
+    pub tick: usize,                     // Advances every frame, drives the spinner
+    pub filter: String,                  // Fuzzy filter for the list
+    pub is_filtering: bool,              // Filter input is open
+    pub sort_mode: SortMode,
+    pub group_mode: GroupMode,
+    pub show_preview: bool,            // Preview pane next to the list
+    preview_workspace: Option<String>, // Workspace the preview was last loaded for
+    pub diff_view: Option<DiffView>,
+    pub create_form: Option<CreateForm>,
+    pub create_progress: Option<CreateProgress>,
+    pub rename_form: Option<RenameForm>,
+    pub message: Option<String>, // Result of the last action, shown until the next key
+    pub keymap: Keymap,
+    pub pending_keys: Vec<KeyPress>, // Keys of a chord typed so far
+    pub show_help: bool,             // Key binding help overlay
+    pub theme: Theme,
+    pub click_areas: ClickAreas,
+    pub last_click: Option<(Instant, usize)>, // Time and row of the last click, for double-clicks
 }
 
 impl App {
@@ -20,6 +152,28 @@ impl App {
             show_delete_dialog: false,
             show_details_dialog: false,
             selected_workspaces: Vec::new(),
+            summaries: HashMap::new(),
+            details: HashMap::new(),
+            sizes: HashMap::new(),
+            pending_loads: Vec::new(),
+            tick: 0,
+            filter: String::new(),
+            is_filtering: false,
+            sort_mode: SortMode::default(),
+            group_mode: GroupMode::default(),
+            show_preview: true,
+            preview_workspace: None,
+            diff_view: None,
+            create_form: None,
+            create_progress: None,
+            rename_form: None,
+            message: None,
+            keymap: Keymap::default(),
+            pending_keys: Vec::new(),
+            show_help: false,
+            theme: Theme::default(),
+            click_areas: ClickAreas::default(),
+            last_click: None,
         }
     }
 
@@ -30,6 +184,21 @@ impl App {
         // Initialize selection state for each workspace
         self.selected_workspaces = vec![false; self.workspaces.len()];
 
+        // Row information is loaded in the background
+        self.summaries.clear();
+        self.details.clear();
+        self.sizes.clear();
+        self.preview_workspace = None;
+        for workspace in &self.workspaces {
+            self.summaries
+                .insert(workspace.name.clone(), LoadState::Loading);
+            self.pending_loads
+                .push(LoadRequest::Summary(workspace.clone()));
+        }
+        if self.sort_mode == SortMode::Size {
+            self.request_sizes();
+        }
+
         if self.workspaces.is_empty() {
             debug!("No workspaces found");
             self.selected_index = 0;
@@ -40,24 +209,232 @@ impl App {
         Ok(())
     }
 
-    #[allow(dead_code)]
+    /// Currently highlighted workspace (None if the filter hides every workspace)
     pub fn get_selected_workspace(&self) -> Option<&WorkspaceInfo> {
-        self.workspaces.get(self.selected_index)
+        let workspace = self.workspaces.get(self.selected_index)?;
+        self.filter_match(workspace).map(|_| workspace)
     }
 
     pub fn next(&mut self) {
-        if !self.workspaces.is_empty() {
-            self.selected_index = (self.selected_index + 1) % self.workspaces.len();
+        let visible = self.visible_indices();
+        if visible.is_empty() {
+            return;
         }
+        self.selected_index = match visible.iter().position(|&i| i == self.selected_index) {
+            Some(pos) => visible[(pos + 1) % visible.len()],
+            None => visible[0],
+        };
     }
 
     pub fn previous(&mut self) {
-        if !self.workspaces.is_empty() {
-            self.selected_index = if self.selected_index == 0 {
-                self.workspaces.len() - 1
+        let visible = self.visible_indices();
+        if visible.is_empty() {
+            return;
+        }
+        self.selected_index = match visible.iter().position(|&i| i == self.selected_index) {
+            Some(0) | None => visible[visible.len() - 1],
+            Some(pos) => visible[pos - 1],
+        };
+    }
+
+    /// Move the cursor by `rows` visible workspaces without wrapping (mouse wheel)
+    pub fn scroll_selection(&mut self, rows: i32) {
+        let visible = self.visible_indices();
+        if visible.is_empty() {
+            return;
+        }
+        let pos = visible
+            .iter()
+            .position(|&i| i == self.selected_index)
+            .unwrap_or(0) as i32;
+        self.selected_index = visible[(pos + rows).clamp(0, visible.len() as i32 - 1) as usize];
+    }
+
+    /// Indices of the workspaces shown with the current filter, in display order
+    pub fn visible_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .workspaces
+            .iter()
+            .enumerate()
+            .filter(|(_, ws)| self.filter_match(ws).is_some())
+            .map(|(i, _)| i)
+            .collect();
+        indices.sort_by(|&a, &b| {
+            let (a, b) = (&self.workspaces[a], &self.workspaces[b]);
+            self.compare_groups(a, b)
+                .then_with(|| self.compare_workspaces(a, b))
+        });
+        indices
+    }
+
+    /// Group a workspace is shown under
+    pub fn group_of<'a>(&self, workspace: &'a WorkspaceInfo) -> Option<&'a str> {
+        self.group_mode.group_of(workspace)
+    }
+
+    /// Groups in name order, workspaces without a group last
+    fn compare_groups(&self, a: &WorkspaceInfo, b: &WorkspaceInfo) -> Ordering {
+        let (a, b) = (self.group_of(a), self.group_of(b));
+        a.is_none().cmp(&b.is_none()).then_with(|| a.cmp(&b))
+    }
+
+    fn compare_workspaces(&self, a: &WorkspaceInfo, b: &WorkspaceInfo) -> Ordering {
+        let order = match self.sort_mode {
+            SortMode::Created => Ordering::Equal,
+            // Descending, so unknown values (None) come last
+            SortMode::LastActivity => self.last_activity(b).cmp(&self.last_activity(a)),
+            SortMode::Name => a.task_name().cmp(b.task_name()),
+            SortMode::Size => self.disk_bytes(b).cmp(&self.disk_bytes(a)),
+            SortMode::DirtyFirst => self.is_dirty(b).cmp(&self.is_dirty(a)),
+        };
+        // Ties (and workspaces whose data is still loading) fall back to creation order
+        order.then_with(|| compare_created(a, b))
+    }
+
+    fn last_activity(&self, workspace: &WorkspaceInfo) -> Option<i64> {
+        match self.summaries.get(&workspace.name) {
+            Some(LoadState::Loaded(summary)) => summary.last_activity,
+            _ => None,
+        }
+    }
+
+    fn disk_bytes(&self, workspace: &WorkspaceInfo) -> Option<u64> {
+        match self.sizes.get(&workspace.name) {
+            Some(LoadState::Loaded(size)) => Some(size.disk_bytes),
+            _ => None,
+        }
+    }
+
+    fn is_dirty(&self, workspace: &WorkspaceInfo) -> bool {
+        match self.summaries.get(&workspace.name) {
+            Some(LoadState::Loaded(summary)) => summary
+                .status
+                .as_ref()
+                .is_some_and(|status| !status.is_clean()),
+            _ => false,
+        }
+    }
+
+    /// Switch to the next sort mode
+    pub fn cycle_sort(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+        if self.sort_mode == SortMode::Size {
+            self.request_sizes();
+        }
+    }
+
+    /// Turn grouping on or off
+    pub fn cycle_group(&mut self) {
+        self.group_mode = self.group_mode.next();
+    }
+
+    /// Sort and group settings to remember for the next session
+    pub fn tui_state(&self) -> TuiState {
+        TuiState {
+            sort: self.sort_mode,
+            group: self.group_mode,
+        }
+    }
+
+    pub fn set_tui_state(&mut self, tui: TuiState) {
+        self.sort_mode = tui.sort;
+        self.group_mode = tui.group;
+    }
+
+    /// Load the sizes not known yet (sizes are otherwise only loaded for the details view)
+    fn request_sizes(&mut self) {
+        for workspace in &self.workspaces {
+            if !self.sizes.contains_key(&workspace.name) {
+                self.sizes
+                    .insert(workspace.name.clone(), LoadState::Loading);
+                self.pending_loads
+                    .push(LoadRequest::Size(workspace.clone()));
+            }
+        }
+    }
+
+    /// Match the filter against task name, branch and path, keeping the best field
+    pub fn filter_match(&self, workspace: &WorkspaceInfo) -> Option<FilterMatch> {
+        if self.filter.is_empty() {
+            return Some(FilterMatch::default());
+        }
+
+        let task = fuzzy_match(&self.filter, workspace.task_name()).map(|m| {
+            // Highlight the task name at the end of the path; directories named otherwise
+            // (e.g. moved by hand) get no highlights
+            let indices = if workspace.path.ends_with(workspace.task_name()) {
+                let offset = workspace.path.chars().count() - workspace.task_name().chars().count();
+                m.indices.iter().map(|i| i + offset).collect()
             } else {
-                self.selected_index - 1
+                Vec::new()
             };
+            (
+                m.score,
+                FilterMatch {
+                    branch: Vec::new(),
+                    path: indices,
+                },
+            )
+        });
+        let branch = fuzzy_match(&self.filter, &workspace.branch).map(|m| {
+            (
+                m.score,
+                FilterMatch {
+                    branch: m.indices,
+                    path: Vec::new(),
+                },
+            )
+        });
+        let path = fuzzy_match(&self.filter, &workspace.path).map(|m| {
+            (
+                m.score,
+                FilterMatch {
+                    branch: Vec::new(),
+                    path: m.indices,
+                },
+            )
+        });
+
+        // On equal scores prefer task name, then branch
+        [task, branch, path]
+            .into_iter()
+            .flatten()
+            .rev()
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, m)| m)
+    }
+
+    pub fn start_filter(&mut self) {
+        self.is_filtering = true;
+    }
+
+    /// Close the filter input, keeping the filter applied
+    pub fn finish_filter(&mut self) {
+        self.is_filtering = false;
+    }
+
+    pub fn clear_filter(&mut self) {
+        self.is_filtering = false;
+        self.filter.clear();
+    }
+
+    pub fn push_filter_char(&mut self, c: char) {
+        self.filter.push(c);
+        self.select_first_visible();
+    }
+
+    pub fn pop_filter_char(&mut self) {
+        self.filter.pop();
+        self.select_first_visible();
+    }
+
+    /// Keep the cursor on a visible workspace after the filter changes
+    fn select_first_visible(&mut self) {
+        let visible = self.visible_indices();
+        if !visible.contains(&self.selected_index) {
+            if let Some(&first) = visible.first() {
+                self.selected_index = first;
+            }
         }
     }
 
@@ -100,6 +477,267 @@ impl App {
 
     pub fn show_details(&mut self) {
         self.show_details_dialog = true;
+
+        // Reload every time the dialog opens, keeping earlier data until it arrives
+        if let Some(workspace) = self.get_selected_workspace().cloned() {
+            self.details
+                .entry(workspace.name.clone())
+                .or_insert(LoadState::Loading);
+            self.pending_loads.push(LoadRequest::Details(workspace));
+        }
+    }
+
+    pub fn toggle_preview(&mut self) {
+        self.show_preview = !self.show_preview;
+    }
+
+    /// Load details for the preview pane when the cursor moves to another workspace
+    pub fn update_preview(&mut self) {
+        if !self.show_preview {
+            return;
+        }
+        let Some(workspace) = self.get_selected_workspace().cloned() else {
+            return;
+        };
+        if self.preview_workspace.as_deref() == Some(workspace.name.as_str()) {
+            return;
+        }
+
+        // Earlier data stays visible until the reload arrives
+        self.details
+            .entry(workspace.name.clone())
+            .or_insert(LoadState::Loading);
+        self.preview_workspace = Some(workspace.name.clone());
+        self.pending_loads.push(LoadRequest::Details(workspace));
+    }
+
+    /// Open the diff viewer for the current workspace, comparing with its base branch
+    pub fn show_diff(&mut self) {
+        if let Some(workspace) = self.get_selected_workspace().cloned() {
+            self.pending_loads
+                .push(LoadRequest::Diff(workspace.clone(), DiffTarget::Base));
+            self.diff_view = Some(DiffView {
+                workspace,
+                target: DiffTarget::Base,
+                diff: LoadState::Loading,
+                selected_file: 0,
+                scroll: 0,
+            });
+        }
+    }
+
+    pub fn hide_diff(&mut self) {
+        self.diff_view = None;
+    }
+
+    pub fn is_in_diff_view(&self) -> bool {
+        self.diff_view.is_some()
+    }
+
+    /// Switch between the diff against the base branch and against HEAD
+    pub fn toggle_diff_target(&mut self) {
+        if let Some(view) = &mut self.diff_view {
+            view.target = view.target.toggle();
+            view.diff = LoadState::Loading;
+            view.selected_file = 0;
+            view.scroll = 0;
+            self.pending_loads
+                .push(LoadRequest::Diff(view.workspace.clone(), view.target));
+        }
+    }
+
+    /// Move to another file of the diff (wrapping around)
+    pub fn select_diff_file(&mut self, forward: bool) {
+        if let Some(view) = &mut self.diff_view {
+            if let LoadState::Loaded(diff) = &view.diff {
+                let count = diff.files.len();
+                if count > 0 {
+                    view.selected_file = if forward {
+                        (view.selected_file + 1) % count
+                    } else {
+                        (view.selected_file + count - 1) % count
+                    };
+                    view.scroll = 0;
+                }
+            }
+        }
+    }
+
+    /// Show a file of the diff by its position in the file list
+    pub fn set_diff_file(&mut self, index: usize) {
+        if let Some(view) = &mut self.diff_view {
+            if view.selected_file != index {
+                view.selected_file = index;
+                view.scroll = 0;
+            }
+        }
+    }
+
+    /// Scroll the diff of the selected file by `lines` (negative scrolls up)
+    pub fn scroll_diff(&mut self, lines: i32) {
+        if let Some(view) = &mut self.diff_view {
+            let line_count = match &view.diff {
+                LoadState::Loaded(diff) => diff
+                    .files
+                    .get(view.selected_file)
+                    .map_or(0, |file| file.lines.len()),
+                _ => 0,
+            };
+            let max_scroll = line_count.saturating_sub(1) as i32;
+            view.scroll = (view.scroll as i32 + lines).clamp(0, max_scroll) as u16;
+        }
+    }
+
+    pub fn show_create_form(&mut self) {
+        self.create_form = Some(CreateForm {
+            task_name: String::new(),
+            base_ref: String::new(),
+            focus: CreateField::TaskName,
+            error: None,
+        });
+    }
+
+    pub fn hide_create_form(&mut self) {
+        self.create_form = None;
+    }
+
+    pub fn is_in_create_form(&self) -> bool {
+        self.create_form.is_some()
+    }
+
+    /// Validate the form and start showing progress; returns (task name, base ref)
+    pub fn submit_create_form(&mut self) -> Option<(String, Option<String>)> {
+        let form = self.create_form.as_mut()?;
+        let task_name = form.task_name.trim().to_string();
+        if task_name.is_empty() {
+            form.error = Some("Task name is required".to_string());
+            return None;
+        }
+        if task_name.contains(char::is_whitespace) || task_name.contains('/') {
+            form.error = Some("Task name cannot contain spaces or '/'".to_string());
+            return None;
+        }
+        let base_ref = Some(form.base_ref.trim().to_string()).filter(|base| !base.is_empty());
+
+        self.create_form = None;
+        self.create_progress = Some(CreateProgress {
+            task_name: task_name.clone(),
+            lines: Vec::new(),
+            scroll_back: 0,
+            result: None,
+        });
+        Some((task_name, base_ref))
+    }
+
+    /// Store output of the background creation
+    pub fn apply_create_event(&mut self, event: CreateEvent) {
+        if let Some(progress) = &mut self.create_progress {
+            match event {
+                CreateEvent::Progress(line) => {
+                    progress.lines.push(line);
+                    // Keep earlier output in place while it is being read
+                    if progress.scroll_back > 0 {
+                        progress.scroll_back += 1;
+                    }
+                }
+                CreateEvent::Finished(result) => {
+                    progress.result = Some(result.map(|workspace| workspace.name))
+                }
+            }
+        }
+    }
+
+    /// Scroll the creation output by `lines` (negative scrolls back to earlier output)
+    pub fn scroll_create_progress(&mut self, lines: i32) {
+        if let Some(progress) = &mut self.create_progress {
+            let max_back = progress.lines.len().saturating_sub(1) as i32;
+            progress.scroll_back =
+                (progress.scroll_back as i32 - lines).clamp(0, max_back) as usize;
+        }
+    }
+
+    pub fn is_creating(&self) -> bool {
+        self.create_progress
+            .as_ref()
+            .is_some_and(|progress| progress.result.is_none())
+    }
+
+    /// Close the progress panel once creation has finished
+    pub fn hide_create_progress(&mut self) {
+        if !self.is_creating() {
+            self.create_progress = None;
+        }
+    }
+
+    /// Open the rename form for the workspace under the cursor, prefilled with its task name
+    pub fn show_rename_form(&mut self) {
+        if let Some(workspace) = self.get_selected_workspace() {
+            self.rename_form = Some(RenameForm {
+                workspace: workspace.clone(),
+                new_name: workspace.task_name().to_string(),
+                error: None,
+            });
+        }
+    }
+
+    pub fn hide_rename_form(&mut self) {
+        self.rename_form = None;
+    }
+
+    pub fn is_in_rename_form(&self) -> bool {
+        self.rename_form.is_some()
+    }
+
+    /// Validate the form; returns (workspace name, new task name).
+    /// The form stays open until the rename succeeds so errors can be shown in it.
+    pub fn submit_rename_form(&mut self) -> Option<(String, String)> {
+        let form = self.rename_form.as_mut()?;
+        let new_name = form.new_name.trim().to_string();
+        if new_name.is_empty() {
+            form.error = Some("Task name is required".to_string());
+            return None;
+        }
+        if new_name.contains(char::is_whitespace) || new_name.contains('/') {
+            form.error = Some("Task name cannot contain spaces or '/'".to_string());
+            return None;
+        }
+        if new_name == form.workspace.task_name() {
+            form.error = Some("Task name is unchanged".to_string());
+            return None;
+        }
+        Some((form.workspace.name.clone(), new_name))
+    }
+
+    /// Show why the rename failed in the still open form
+    pub fn set_rename_error(&mut self, error: String) {
+        if let Some(form) = &mut self.rename_form {
+            form.error = Some(error);
+        }
+    }
+
+    /// Move the cursor to a workspace, clearing a filter that hides it
+    pub fn select_workspace(&mut self, workspace_name: &str) {
+        if let Some(index) = self
+            .workspaces
+            .iter()
+            .position(|w| w.name == workspace_name)
+        {
+            if self.filter_match(&self.workspaces[index]).is_none() {
+                self.clear_filter();
+            }
+            self.selected_index = index;
+        }
+    }
+
+    /// Record a lock change made from the TUI
+    pub fn set_locked(&mut self, workspace_name: &str, locked: Option<String>) {
+        if let Some(workspace) = self
+            .workspaces
+            .iter_mut()
+            .find(|w| w.name == workspace_name)
+        {
+            workspace.locked = locked;
+        }
     }
 
     pub fn hide_details(&mut self) {
@@ -110,38 +748,107 @@ impl App {
         self.show_details_dialog
     }
 
+    /// Hand pending requests over to the loader
+    pub fn take_load_requests(&mut self) -> Vec<LoadRequest> {
+        std::mem::take(&mut self.pending_loads)
+    }
+
+    /// Store a result from the background loader
+    pub fn apply_load_result(&mut self, result: LoadResult) {
+        match result {
+            LoadResult::Summary { name, summary } => {
+                self.summaries.insert(name, LoadState::Loaded(summary));
+            }
+            LoadResult::Details { name, details } => {
+                let state = match details {
+                    Ok(details) => {
+                        if let Some(usage) = &details.disk_usage {
+                            self.sizes
+                                .insert(name.clone(), LoadState::Loaded(usage.clone()));
+                        } else {
+                            self.sizes.entry(name.clone()).or_insert(LoadState::Loading);
+                        }
+                        LoadState::Loaded(*details)
+                    }
+                    Err(e) => LoadState::Failed(e),
+                };
+                self.details.insert(name, state);
+            }
+            LoadResult::Size { name, size } => {
+                let state = match size {
+                    SizeState::Ready(usage) => LoadState::Loaded(usage),
+                    SizeState::Failed(e) => LoadState::Failed(e),
+                    SizeState::Calculating => LoadState::Loading,
+                };
+                self.sizes.insert(name, state);
+            }
+            LoadResult::Diff { name, target, diff } => {
+                // Ignore results for a view that was closed or switched meanwhile
+                if let Some(view) = &mut self.diff_view {
+                    if view.workspace.name == name && view.target == target {
+                        view.diff = match diff {
+                            Ok(diff) => LoadState::Loaded(diff),
+                            Err(e) => LoadState::Failed(e),
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Advance the spinner animation
+    pub fn on_tick(&mut self) {
+        self.tick = self.tick.wrapping_add(1);
+    }
+
+    pub fn spinner(&self) -> &'static str {
+        SPINNER_FRAMES[self.tick % SPINNER_FRAMES.len()]
+    }
+
     /// Toggle selection state of current workspace
     pub fn toggle_current_selection(&mut self) {
-        if self.selected_index < self.selected_workspaces.len() {
+        if self.get_selected_workspace().is_some()
+            && self.selected_index < self.selected_workspaces.len()
+        {
             self.selected_workspaces[self.selected_index] =
                 !self.selected_workspaces[self.selected_index];
         }
     }
 
-    /// Toggle all workspaces selection state
+    /// Toggle selection state of all workspaces shown with the current filter
     pub fn toggle_all_selection(&mut self) {
-        let all_selected = self.selected_workspaces.iter().all(|&selected| selected);
+        let visible = self.visible_indices();
+        let all_selected = visible
+            .iter()
+            .all(|&i| self.selected_workspaces.get(i).copied().unwrap_or(false));
         let new_state = !all_selected;
 
-        for selected in &mut self.selected_workspaces {
-            *selected = new_state;
+        for i in visible {
+            if let Some(selected) = self.selected_workspaces.get_mut(i) {
+                *selected = new_state;
+            }
         }
     }
 
-    /// Get number of selected workspaces
+    /// Get number of selected workspaces shown with the current filter
     pub fn get_selected_count(&self) -> usize {
-        self.selected_workspaces
-            .iter()
-            .filter(|&&selected| selected)
-            .count()
+        self.get_selected_indices().len()
     }
 
-    /// Get indices of selected workspaces
+    /// Get indices of selected workspaces; rows hidden by the filter keep their selection
+    /// but are left out until the filter shows them again
     pub fn get_selected_indices(&self) -> Vec<usize> {
         self.selected_workspaces
             .iter()
             .enumerate()
-            .filter_map(|(i, &selected)| if selected { Some(i) } else { None })
+            .filter(|&(i, &selected)| {
+                selected
+                    && self
+                        .workspaces
+                        .get(i)
+                        .is_some_and(|ws| self.filter_match(ws).is_some())
+            })
+            .map(|(i, _)| i)
             .collect()
     }
 
@@ -170,10 +877,21 @@ impl App {
     }
 }
 
+/// Creation order: timestamped workspaces oldest first, then other names
+fn compare_created(a: &WorkspaceInfo, b: &WorkspaceInfo) -> Ordering {
+    let (a_created, b_created) = (a.created_at(), b.created_at());
+    a_created
+        .is_none()
+        .cmp(&b_created.is_none())
+        .then_with(|| a_created.cmp(&b_created))
+        .then_with(|| a.name.cmp(&b.name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::workspace::WorkspaceInfo;
+    use crate::workspace::WorkspaceStatus;
 
     #[test]
     fn test_app_new() {
@@ -184,6 +902,8 @@ mod tests {
         assert!(!app.show_delete_dialog);
         assert!(!app.show_details_dialog);
         assert!(app.selected_workspaces.is_empty());
+        assert!(app.summaries.is_empty());
+        assert!(app.pending_loads.is_empty());
     }
 
     #[test]
@@ -206,11 +926,13 @@ mod tests {
                 name: "workspace1".to_string(),
                 path: "/path1".to_string(),
                 branch: "branch1".to_string(),
+                locked: None,
             },
             WorkspaceInfo {
                 name: "workspace2".to_string(),
                 path: "/path2".to_string(),
                 branch: "branch2".to_string(),
+                locked: None,
             },
         ];
 
@@ -247,6 +969,7 @@ mod tests {
             name: "workspace1".to_string(),
             path: "/path1".to_string(),
             branch: "branch1".to_string(),
+            locked: None,
         }];
 
         let selected = app.get_selected_workspace();
@@ -281,11 +1004,13 @@ mod tests {
                 name: "workspace1".to_string(),
                 path: "/path1".to_string(),
                 branch: "branch1".to_string(),
+                locked: None,
             },
             WorkspaceInfo {
                 name: "workspace2".to_string(),
                 path: "/path2".to_string(),
                 branch: "branch2".to_string(),
+                locked: None,
             },
         ];
         app.selected_workspaces = vec![false, false];
@@ -310,11 +1035,13 @@ mod tests {
                 name: "workspace1".to_string(),
                 path: "/path1".to_string(),
                 branch: "branch1".to_string(),
+                locked: None,
             },
             WorkspaceInfo {
                 name: "workspace2".to_string(),
                 path: "/path2".to_string(),
                 branch: "branch2".to_string(),
+                locked: None,
             },
         ];
         app.selected_workspaces = vec![false, false];
@@ -338,6 +1065,423 @@ mod tests {
         assert!(!app.is_in_details_view());
     }
 
+    #[test]
+    fn test_show_details_requests_load() {
+        let mut app = App::new();
+        app.workspaces = vec![WorkspaceInfo {
+            name: "workspace1".to_string(),
+            path: "/path1".to_string(),
+            branch: "branch1".to_string(),
+            locked: None,
+        }];
+
+        app.show_details();
+        assert_eq!(app.details.get("workspace1"), Some(&LoadState::Loading));
+        let requests = app.take_load_requests();
+        assert_eq!(requests.len(), 1);
+        assert!(matches!(&requests[0], LoadRequest::Details(ws) if ws.name == "workspace1"));
+        assert!(app.take_load_requests().is_empty());
+    }
+
+    #[test]
+    fn test_apply_load_results() {
+        let mut app = App::new();
+        app.summaries
+            .insert("workspace1".to_string(), LoadState::Loading);
+
+        app.apply_load_result(LoadResult::Summary {
+            name: "workspace1".to_string(),
+            summary: WorkspaceSummary {
+                status: None,
+                tracking: Default::default(),
+                last_activity: None,
+            },
+        });
+        assert!(matches!(
+            app.summaries.get("workspace1"),
+            Some(LoadState::Loaded(_))
+        ));
+
+        app.apply_load_result(LoadResult::Details {
+            name: "workspace1".to_string(),
+            details: Err("failed".to_string()),
+        });
+        assert_eq!(
+            app.details.get("workspace1"),
+            Some(&LoadState::Failed("failed".to_string()))
+        );
+
+        app.apply_load_result(LoadResult::Size {
+            name: "workspace1".to_string(),
+            size: SizeState::Ready(DirectorySize::default()),
+        });
+        assert_eq!(
+            app.sizes.get("workspace1"),
+            Some(&LoadState::Loaded(DirectorySize::default()))
+        );
+    }
+
+    fn filter_test_app() -> App {
+        let mut app = App::new();
+        app.workspaces = vec![
+            WorkspaceInfo {
+                name: "20250625-120000-feature-auth".to_string(),
+                path: "/ws/20250625-120000-feature-auth".to_string(),
+                branch: "work/20250625-120000-feature-auth".to_string(),
+                locked: None,
+            },
+            WorkspaceInfo {
+                name: "20250625-130000-bugfix-login".to_string(),
+                path: "/ws/20250625-130000-bugfix-login".to_string(),
+                branch: "work/20250625-130000-bugfix-login".to_string(),
+                locked: None,
+            },
+            WorkspaceInfo {
+                name: "20250625-140000-docs".to_string(),
+                path: "/other/20250625-140000-docs".to_string(),
+                branch: "docs-update".to_string(),
+                locked: None,
+            },
+        ];
+        app.selected_workspaces = vec![false; 3];
+        app
+    }
+
+    #[test]
+    fn test_filter_narrows_visible_workspaces() {
+        let mut app = filter_test_app();
+        assert_eq!(app.visible_indices(), vec![0, 1, 2]);
+
+        app.start_filter();
+        for c in "login".chars() {
+            app.push_filter_char(c);
+        }
+        assert_eq!(app.visible_indices(), vec![1]);
+        // Cursor moves onto the only match
+        assert_eq!(
+            app.get_selected_workspace().unwrap().name,
+            "20250625-130000-bugfix-login"
+        );
+
+        // Matches on the path too
+        app.filter = "other".to_string();
+        assert_eq!(app.visible_indices(), vec![2]);
+
+        // Nothing matches
+        app.filter = "zzz".to_string();
+        assert!(app.visible_indices().is_empty());
+        assert!(app.get_selected_workspace().is_none());
+
+        app.clear_filter();
+        assert!(!app.is_filtering);
+        assert_eq!(app.visible_indices(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_filter_match_highlights_task_in_path() {
+        let mut app = filter_test_app();
+        app.filter = "auth".to_string();
+
+        let m = app.filter_match(&app.workspaces[0]).unwrap();
+        let path: Vec<char> = app.workspaces[0].path.chars().collect();
+        let highlighted: String = m.path.iter().map(|&i| path[i]).collect();
+        assert_eq!(highlighted, "auth");
+        assert!(m.branch.is_empty());
+
+        // A directory that isn't named after the workspace, shorter than the task name
+        app.workspaces[0].path = "/ws/a".to_string();
+        let m = app.filter_match(&app.workspaces[0]).unwrap();
+        assert!(m.path.is_empty());
+    }
+
+    #[test]
+    fn test_navigation_and_selection_on_filtered_view() {
+        let mut app = filter_test_app();
+        app.filter = "work".to_string(); // branches of the first two
+
+        app.selected_index = 0;
+        app.next();
+        assert_eq!(app.selected_index, 1);
+        app.next();
+        assert_eq!(app.selected_index, 0);
+        app.previous();
+        assert_eq!(app.selected_index, 1);
+
+        // "Select all" only affects visible workspaces
+        app.toggle_all_selection();
+        assert_eq!(app.get_selected_indices(), vec![0, 1]);
+
+        // Selected rows hidden by the filter are not acted on, e.g. by delete
+        app.filter = "login".to_string();
+        assert_eq!(app.get_selected_count(), 1);
+        assert_eq!(
+            app.get_selected_workspaces()[0].name,
+            "20250625-130000-bugfix-login"
+        );
+        app.clear_filter();
+        assert_eq!(app.get_selected_count(), 2);
+    }
+
+    fn loaded_summary(dirty: bool, last_activity: i64) -> LoadState<WorkspaceSummary> {
+        let status = WorkspaceStatus {
+            unstaged: usize::from(dirty),
+            ..Default::default()
+        };
+        LoadState::Loaded(WorkspaceSummary {
+            status: Some(status),
+            tracking: Default::default(),
+            last_activity: Some(last_activity),
+        })
+    }
+
+    #[test]
+    fn test_sort_modes() {
+        let mut app = filter_test_app();
+        let order = |app: &App| -> Vec<usize> { app.visible_indices() };
+
+        // Creation order by default
+        assert_eq!(order(&app), vec![0, 1, 2]);
+
+        app.sort_mode = SortMode::Name;
+        // bugfix-login, docs, feature-auth
+        assert_eq!(order(&app), vec![1, 2, 0]);
+
+        app.sort_mode = SortMode::LastActivity;
+        app.summaries
+            .insert(app.workspaces[0].name.clone(), loaded_summary(false, 100));
+        app.summaries
+            .insert(app.workspaces[2].name.clone(), loaded_summary(true, 300));
+        // Workspaces still loading come last
+        assert_eq!(order(&app), vec![2, 0, 1]);
+
+        app.sort_mode = SortMode::DirtyFirst;
+        assert_eq!(order(&app), vec![2, 0, 1]);
+
+        app.sort_mode = SortMode::Size;
+        app.sizes.insert(
+            app.workspaces[1].name.clone(),
+            LoadState::Loaded(DirectorySize {
+                disk_bytes: 10,
+                ..Default::default()
+            }),
+        );
+        assert_eq!(order(&app), vec![1, 0, 2]);
+
+        // Navigation follows the display order
+        app.selected_index = 1;
+        app.next();
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn test_switching_to_size_sort_requests_sizes() {
+        let mut app = filter_test_app();
+        app.sizes
+            .insert(app.workspaces[0].name.clone(), LoadState::Loading);
+
+        app.sort_mode = SortMode::Name;
+        app.cycle_sort();
+        assert_eq!(app.sort_mode, SortMode::Size);
+
+        let requested: Vec<String> = app
+            .take_load_requests()
+            .into_iter()
+            .map(|request| match request {
+                LoadRequest::Size(workspace) => workspace.name,
+                other => panic!("unexpected request: {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            requested,
+            vec!["20250625-130000-bugfix-login", "20250625-140000-docs"]
+        );
+    }
+
+    #[test]
+    fn test_group_by_branch_prefix() {
+        let mut app = filter_test_app();
+        app.sort_mode = SortMode::Name;
+        app.set_tui_state(TuiState {
+            sort: app.sort_mode,
+            group: GroupMode::BranchPrefix,
+        });
+
+        // "work/" group first, the branch without a prefix last
+        assert_eq!(app.visible_indices(), vec![1, 0, 2]);
+        assert_eq!(app.group_of(&app.workspaces[0]), Some("work"));
+        assert_eq!(app.group_of(&app.workspaces[2]), None);
+
+        app.cycle_group();
+        assert_eq!(app.tui_state().group, GroupMode::None);
+        assert_eq!(app.visible_indices(), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn test_preview_follows_cursor() {
+        let mut app = filter_test_app();
+
+        app.update_preview();
+        app.update_preview(); // Same workspace is not loaded twice
+        app.next();
+        app.update_preview();
+        let requested: Vec<String> = app
+            .take_load_requests()
+            .into_iter()
+            .map(|request| match request {
+                LoadRequest::Details(workspace) => workspace.name,
+                other => panic!("unexpected request: {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            requested,
+            vec![
+                "20250625-120000-feature-auth",
+                "20250625-130000-bugfix-login"
+            ]
+        );
+        assert_eq!(
+            app.details.get("20250625-130000-bugfix-login"),
+            Some(&LoadState::Loading)
+        );
+
+        // Nothing is loaded while the preview is hidden
+        app.toggle_preview();
+        app.next();
+        app.update_preview();
+        assert!(app.take_load_requests().is_empty());
+    }
+
+    #[test]
+    fn test_diff_view() {
+        use crate::diff::{DiffLine, DiffLineKind, FileDiff};
+
+        let mut app = filter_test_app();
+        app.show_diff();
+        assert!(app.is_in_diff_view());
+        assert!(matches!(
+            &app.take_load_requests()[..],
+            [LoadRequest::Diff(workspace, DiffTarget::Base)]
+                if workspace.name == "20250625-120000-feature-auth"
+        ));
+
+        // A result for the other target is stale after switching
+        app.toggle_diff_target();
+        app.apply_load_result(LoadResult::Diff {
+            name: "20250625-120000-feature-auth".to_string(),
+            target: DiffTarget::Base,
+            diff: Ok(WorkspaceDiff::default()),
+        });
+        assert_eq!(app.diff_view.as_ref().unwrap().diff, LoadState::Loading);
+
+        let file = |path: &str, lines: usize| FileDiff {
+            path: path.to_string(),
+            status: 'M',
+            additions: lines,
+            deletions: 0,
+            lines: vec![
+                DiffLine {
+                    kind: DiffLineKind::Addition,
+                    text: "+x".to_string(),
+                };
+                lines
+            ],
+        };
+        app.apply_load_result(LoadResult::Diff {
+            name: "20250625-120000-feature-auth".to_string(),
+            target: DiffTarget::Head,
+            diff: Ok(WorkspaceDiff {
+                compared_with: "HEAD".to_string(),
+                files: vec![file("a.rs", 5), file("b.rs", 2)],
+            }),
+        });
+
+        // Scrolling stays within the selected file
+        app.scroll_diff(3);
+        assert_eq!(app.diff_view.as_ref().unwrap().scroll, 3);
+        app.scroll_diff(100);
+        assert_eq!(app.diff_view.as_ref().unwrap().scroll, 4);
+        app.scroll_diff(-100);
+        assert_eq!(app.diff_view.as_ref().unwrap().scroll, 0);
+
+        app.select_diff_file(false);
+        assert_eq!(app.diff_view.as_ref().unwrap().selected_file, 1);
+        app.select_diff_file(true);
+        assert_eq!(app.diff_view.as_ref().unwrap().selected_file, 0);
+
+        app.hide_diff();
+        assert!(!app.is_in_diff_view());
+    }
+
+    #[test]
+    fn test_create_form() {
+        let mut app = filter_test_app();
+        app.show_create_form();
+        assert!(app.is_in_create_form());
+
+        // Task name is required
+        assert_eq!(app.submit_create_form(), None);
+        assert!(app.create_form.as_ref().unwrap().error.is_some());
+
+        let form = app.create_form.as_mut().unwrap();
+        form.task_name = "new-task".to_string();
+        form.base_ref = "  ".to_string();
+        assert_eq!(
+            app.submit_create_form(),
+            Some(("new-task".to_string(), None))
+        );
+        assert!(!app.is_in_create_form());
+        assert!(app.is_creating());
+
+        // The panel stays open while the workspace is being created
+        app.apply_create_event(CreateEvent::Progress("🚀 Creating workspace:".to_string()));
+        app.hide_create_progress();
+        assert!(app.is_creating());
+
+        // Scrolling back keeps earlier output in place as new lines arrive
+        app.apply_create_event(CreateEvent::Progress("📋 Copying files".to_string()));
+        app.scroll_create_progress(-3);
+        assert_eq!(app.create_progress.as_ref().unwrap().scroll_back, 1);
+        app.apply_create_event(CreateEvent::Progress("✅ Copied".to_string()));
+        assert_eq!(app.create_progress.as_ref().unwrap().scroll_back, 2);
+        app.scroll_create_progress(5);
+        assert_eq!(app.create_progress.as_ref().unwrap().scroll_back, 0);
+
+        let created = WorkspaceInfo {
+            name: "20250625-150000-new-task".to_string(),
+            path: "/ws/20250625-150000-new-task".to_string(),
+            branch: "work/20250625-150000-new-task".to_string(),
+            locked: None,
+        };
+        app.apply_create_event(CreateEvent::Finished(Ok(created.clone())));
+        let progress = app.create_progress.as_ref().unwrap();
+        assert_eq!(
+            progress.lines,
+            vec!["🚀 Creating workspace:", "📋 Copying files", "✅ Copied"]
+        );
+        assert_eq!(progress.result, Some(Ok(created.name.clone())));
+        app.hide_create_progress();
+        assert!(app.create_progress.is_none());
+
+        // The new workspace is selected even if the filter hides it
+        app.workspaces.push(created);
+        app.selected_workspaces.push(false);
+        app.filter = "docs".to_string();
+        app.select_workspace("20250625-150000-new-task");
+        assert!(app.filter.is_empty());
+        assert_eq!(
+            app.get_selected_workspace().unwrap().name,
+            "20250625-150000-new-task"
+        );
+    }
+
+    #[test]
+    fn test_spinner_advances() {
+        let mut app = App::new();
+        let first = app.spinner();
+        app.on_tick();
+        assert_ne!(app.spinner(), first);
+    }
+
     #[test]
     fn test_multi_selection() {
         let mut app = App::new();
@@ -346,11 +1490,13 @@ mod tests {
                 name: "workspace1".to_string(),
                 path: "/path1".to_string(),
                 branch: "branch1".to_string(),
+                locked: None,
             },
             WorkspaceInfo {
                 name: "workspace2".to_string(),
                 path: "/path2".to_string(),
                 branch: "branch2".to_string(),
+                locked: None,
             },
         ];
         app.selected_workspaces = vec![false, false];
@@ -392,11 +1538,13 @@ mod tests {
                 name: "workspace1".to_string(),
                 path: "/path1".to_string(),
                 branch: "branch1".to_string(),
+                locked: None,
             },
             WorkspaceInfo {
                 name: "workspace2".to_string(),
                 path: "/path2".to_string(),
                 branch: "branch2".to_string(),
+                locked: None,
             },
         ];
         app.selected_workspaces = vec![false, false];
@@ -0,0 +1,41 @@
+use crate::config::WorkspaceSettings;
+use crate::error::GitwsResult;
+use crate::workspace::{WorkspaceInfo, WorkspaceManager};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use tracing::debug;
+
+/// Output of a workspace creation running in the background
+#[derive(Debug)]
+pub enum CreateEvent {
+    /// Line of copy/setup output
+    Progress(String),
+    Finished(Result<WorkspaceInfo, String>),
+}
+
+/// Create a workspace on another thread so the TUI keeps drawing while
+/// files are copied and pre-commands run
+pub fn spawn_create(
+    workspace_manager: &WorkspaceManager,
+    settings: WorkspaceSettings,
+    task_name: String,
+    base: Option<String>,
+) -> GitwsResult<Receiver<CreateEvent>> {
+    let manager = workspace_manager.try_clone()?;
+    let (sender, receiver) = mpsc::channel();
+
+    debug!("Creating workspace {} in the background", task_name);
+    thread::spawn(move || {
+        let progress_sender = sender.clone();
+        let result = manager
+            .create_workspace(&task_name, &settings, base.as_deref(), &mut |line| {
+                for line in line.lines() {
+                    let _ = progress_sender.send(CreateEvent::Progress(line.to_string()));
+                }
+            })
+            .map_err(|e| e.to_string());
+        let _ = sender.send(CreateEvent::Finished(result));
+    });
+
+    Ok(receiver)
+}
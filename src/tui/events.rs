@@ -1,6 +1,20 @@
+use crate::tui::app::{CreateField, DialogButton};
+use crate::tui::keymap::{Action, KeyMatch, KeyPress};
+use crate::tui::open::OpenTarget;
 use crate::tui::App;
-use crossterm::event::{self, Event, KeyCode};
-use std::time::Duration;
+use crate::workspace::WorkspaceInfo;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+use std::time::{Duration, Instant};
+
+/// Lines scrolled by PageUp/PageDown in the diff viewer
+const DIFF_PAGE_LINES: i32 = 20;
+
+/// Lines scrolled by one step of the mouse wheel in the diff viewer and the creation output
+const WHEEL_LINES: i32 = 3;
+
+/// Longest gap between the clicks of a double-click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
 
 #[derive(Debug, PartialEq)]
 pub enum AppAction {
@@ -8,135 +22,383 @@ pub enum AppAction {
     Quit,
     NavigateToWorkspace(String),   // Return path
     DeleteWorkspaces(Vec<String>), // Workspace names to delete (supports bulk delete)
+    CreateWorkspace {
+        task_name: String,
+        base_ref: Option<String>, // None creates from the current HEAD
+    },
+    RenameWorkspace {
+        workspace_name: String,
+        new_task_name: String,
+    },
+    OpenWorkspace(OpenTarget), // Launch a program for the selected workspace
+    ToggleLock(String),        // Workspace name to lock or unlock
+    Refresh,                   // Reload the list, recalculating sizes
 }
 
 pub fn handle_events(app: &mut App) -> std::io::Result<AppAction> {
     if event::poll(Duration::from_millis(100))? {
-        if let Event::Key(key) = event::read()? {
-            match key.code {
-                KeyCode::Char('q') | KeyCode::Esc => {
-                    if app.is_in_delete_confirmation() {
-                        app.hide_delete_confirmation();
-                        Ok(AppAction::None)
-                    } else if app.is_in_details_view() {
-                        app.hide_details();
-                        Ok(AppAction::None)
-                    } else {
-                        app.quit();
-                        Ok(AppAction::Quit)
-                    }
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if !app.is_in_delete_confirmation() && !app.is_in_details_view() {
-                        app.next();
-                    }
-                    Ok(AppAction::None)
-                }
-                KeyCode::Up | KeyCode::Char('k') => {
-                    if !app.is_in_delete_confirmation() && !app.is_in_details_view() {
-                        app.previous();
-                    }
-                    Ok(AppAction::None)
-                }
-                KeyCode::Enter => {
-                    if app.is_in_delete_confirmation() {
-                        // Enter key handling in delete confirmation dialog
-                        Ok(AppAction::None)
-                    } else if app.is_in_details_view() {
-                        // Enter key handling in details dialog (close)
-                        app.hide_details();
-                        Ok(AppAction::None)
-                    } else if let Some(workspace) = app.get_selected_workspace() {
-                        Ok(AppAction::NavigateToWorkspace(workspace.path.clone()))
-                    } else {
-                        Ok(AppAction::None)
-                    }
-                }
-                KeyCode::Char('d') => {
-                    if !app.is_in_delete_confirmation() && !app.is_in_details_view() {
-                        // Check if any workspaces are selected, or use current workspace
-                        let selected_count = app.get_selected_count();
-                        if selected_count > 0 || app.get_selected_workspace().is_some() {
-                            app.show_delete_confirmation();
-                        }
-                    }
-                    Ok(AppAction::None)
-                }
-                KeyCode::Char('y') | KeyCode::Char('Y') => {
-                    if app.is_in_delete_confirmation() {
-                        let selected_count = app.get_selected_count();
-                        let workspace_names = if selected_count > 0 {
-                            // Use selected workspaces
-                            app.get_selected_workspaces()
-                                .into_iter()
-                                .map(|w| w.name.clone())
-                                .collect()
-                        } else if let Some(workspace) = app.get_selected_workspace() {
-                            // Use current workspace if none are selected
-                            vec![workspace.name.clone()]
-                        } else {
-                            vec![]
-                        };
-
-                        if !workspace_names.is_empty() {
-                            app.hide_delete_confirmation();
-                            Ok(AppAction::DeleteWorkspaces(workspace_names))
-                        } else {
-                            Ok(AppAction::None)
-                        }
-                    } else {
-                        Ok(AppAction::None)
-                    }
-                }
-                KeyCode::Char('n') | KeyCode::Char('N') => {
-                    if app.is_in_delete_confirmation() {
-                        app.hide_delete_confirmation();
-                    }
-                    Ok(AppAction::None)
-                }
-                KeyCode::Char('i') => {
-                    if !app.is_in_delete_confirmation()
-                        && !app.is_in_details_view()
-                        && app.get_selected_workspace().is_some()
-                    {
-                        app.show_details();
-                    }
-                    Ok(AppAction::None)
-                }
-                KeyCode::Char(' ') => {
-                    // Space key: toggle selection of current workspace
-                    if !app.is_in_delete_confirmation() && !app.is_in_details_view() {
-                        app.toggle_current_selection();
-                    }
-                    Ok(AppAction::None)
-                }
-                KeyCode::Char('a') => {
-                    // 'a' key: toggle all workspaces selection
-                    if !app.is_in_delete_confirmation() && !app.is_in_details_view() {
-                        app.toggle_all_selection();
-                    }
-                    Ok(AppAction::None)
-                }
-                _ => {
-                    // Close details dialog with any key
-                    if app.is_in_details_view() {
-                        app.hide_details();
-                    }
-                    Ok(AppAction::None)
+        match event::read()? {
+            Event::Key(key) => return Ok(handle_key(app, key)),
+            Event::Mouse(mouse) => return Ok(handle_mouse(app, mouse)),
+            _ => {}
+        }
+    }
+    Ok(AppAction::None)
+}
+
+/// Dispatch a key press to the open dialog or, on the list, through the keymap
+pub fn handle_key(app: &mut App, key: KeyEvent) -> AppAction {
+    app.message = None;
+    if app.is_filtering {
+        return handle_filter_input(app, key.code);
+    }
+    if app.is_in_diff_view() {
+        return handle_diff_view(app, key.code);
+    }
+    if app.is_in_create_form() {
+        return handle_create_form(app, key.code);
+    }
+    if app.is_in_rename_form() {
+        return handle_rename_form(app, key.code);
+    }
+    if app.create_progress.is_some() {
+        if matches!(key.code, KeyCode::Enter | KeyCode::Esc | KeyCode::Char('q')) {
+            app.hide_create_progress();
+        }
+        return AppAction::None;
+    }
+    if app.show_help {
+        // Close the help overlay with any key
+        app.show_help = false;
+        return AppAction::None;
+    }
+    if app.is_in_details_view() {
+        // Close details dialog with Enter, q or Esc; other keys are ignored
+        if matches!(key.code, KeyCode::Enter | KeyCode::Char('q') | KeyCode::Esc) {
+            app.hide_details();
+        }
+        return AppAction::None;
+    }
+    if app.is_in_delete_confirmation() {
+        return handle_delete_confirmation(app, key.code);
+    }
+
+    if key.code == KeyCode::Esc && !app.pending_keys.is_empty() {
+        // Esc cancels a chord
+        app.pending_keys.clear();
+        return AppAction::None;
+    }
+    if key.code == KeyCode::Esc && !app.filter.is_empty() {
+        // Esc clears an applied filter before quitting
+        app.clear_filter();
+        return AppAction::None;
+    }
+
+    app.pending_keys.push(KeyPress::from(key));
+    match app.keymap.resolve(&app.pending_keys) {
+        KeyMatch::Pending => AppAction::None,
+        KeyMatch::Action(action) => {
+            app.pending_keys.clear();
+            run_action(app, action)
+        }
+        KeyMatch::Unbound => {
+            app.pending_keys.clear();
+            AppAction::None
+        }
+    }
+}
+
+/// Dispatch a mouse event using the regions of the last drawn frame
+pub fn handle_mouse(app: &mut App, mouse: MouseEvent) -> AppAction {
+    let position = Position::new(mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            app.message = None;
+            handle_click(app, position)
+        }
+        MouseEventKind::ScrollDown => {
+            handle_scroll(app, 1);
+            AppAction::None
+        }
+        MouseEventKind::ScrollUp => {
+            handle_scroll(app, -1);
+            AppAction::None
+        }
+        // Movement, drags and other buttons
+        _ => AppAction::None,
+    }
+}
+
+fn handle_click(app: &mut App, position: Position) -> AppAction {
+    if app.is_in_diff_view() {
+        if let Some(index) = app.click_areas.diff_file_at(position) {
+            app.set_diff_file(index);
+        }
+        return AppAction::None;
+    }
+    if app.is_in_create_form() || app.is_in_rename_form() || app.create_progress.is_some() {
+        return AppAction::None;
+    }
+    if app.show_help {
+        // Close the help overlay with any click
+        app.show_help = false;
+        return AppAction::None;
+    }
+    if app.is_in_details_view() {
+        app.hide_details();
+        return AppAction::None;
+    }
+    if app.is_in_delete_confirmation() {
+        return match app.click_areas.button_at(position) {
+            Some(DialogButton::Confirm) => handle_delete_confirmation(app, KeyCode::Char('y')),
+            Some(DialogButton::Cancel) => handle_delete_confirmation(app, KeyCode::Char('n')),
+            None => AppAction::None,
+        };
+    }
+
+    app.pending_keys.clear();
+    if let Some(index) = app.click_areas.checkbox_at(position) {
+        app.selected_index = index;
+        app.toggle_current_selection();
+        app.last_click = None;
+        return AppAction::None;
+    }
+    let Some(index) = app.click_areas.row_at(position) else {
+        app.last_click = None;
+        return AppAction::None;
+    };
+    let double_click = app.last_click.is_some_and(|(time, last_index)| {
+        last_index == index && time.elapsed() <= DOUBLE_CLICK_INTERVAL
+    });
+    app.selected_index = index;
+    if double_click {
+        app.last_click = None;
+        return run_action(app, Action::Navigate);
+    }
+    app.last_click = Some((Instant::now(), index));
+    AppAction::None
+}
+
+/// Mouse wheel: scroll the diff viewer or the creation output, or move the cursor of the list
+///
+/// The details and help overlays have a fixed layout, so the wheel leaves them alone.
+fn handle_scroll(app: &mut App, lines: i32) {
+    if app.is_in_diff_view() {
+        app.scroll_diff(lines * WHEEL_LINES);
+    } else if app.create_progress.is_some() {
+        app.scroll_create_progress(lines * WHEEL_LINES);
+    } else if !(app.is_in_create_form()
+        || app.is_in_rename_form()
+        || app.show_help
+        || app.is_in_details_view()
+        || app.is_in_delete_confirmation())
+    {
+        app.scroll_selection(lines);
+    }
+}
+
+fn run_action(app: &mut App, action: Action) -> AppAction {
+    match action {
+        Action::Quit => {
+            app.quit();
+            return AppAction::Quit;
+        }
+        Action::Down => app.next(),
+        Action::Up => app.previous(),
+        Action::Navigate => {
+            if let Some(workspace) = app.get_selected_workspace() {
+                return AppAction::NavigateToWorkspace(workspace.path.clone());
+            }
+        }
+        Action::ToggleSelect => app.toggle_current_selection(),
+        Action::SelectAll => app.toggle_all_selection(),
+        Action::Filter => app.start_filter(),
+        Action::Sort => app.cycle_sort(),
+        Action::Group => app.cycle_group(),
+        Action::Preview => app.toggle_preview(),
+        Action::Diff => app.show_diff(),
+        Action::New => app.show_create_form(),
+        Action::Rename => app.show_rename_form(),
+        Action::Delete => {
+            // Check if any workspaces are selected, or use current workspace
+            let targets: Vec<&WorkspaceInfo> = if app.get_selected_count() > 0 {
+                app.get_selected_workspaces()
+            } else {
+                app.get_selected_workspace().into_iter().collect()
+            };
+            // Locked workspaces are in use; they have to be unlocked before deleting
+            if let Some(locked) = targets.iter().find(|w| w.is_locked()) {
+                app.message = Some(format!("🔒 {} is locked; unlock it first", locked.name));
+            } else if !targets.is_empty() {
+                app.show_delete_confirmation();
+            }
+        }
+        Action::Lock => {
+            if let Some(workspace) = app.get_selected_workspace() {
+                return AppAction::ToggleLock(workspace.name.clone());
+            }
+        }
+        Action::Details => {
+            if app.get_selected_workspace().is_some() {
+                app.show_details();
+            }
+        }
+        Action::Refresh => return AppAction::Refresh,
+        Action::OpenEditor | Action::OpenTerminal | Action::OpenCustom => {
+            if app.get_selected_workspace().is_some() {
+                let target = match action {
+                    Action::OpenEditor => OpenTarget::Editor,
+                    Action::OpenTerminal => OpenTarget::Terminal,
+                    _ => OpenTarget::Custom,
+                };
+                return AppAction::OpenWorkspace(target);
+            }
+        }
+        Action::Help => app.show_help = true,
+    }
+    AppAction::None
+}
+
+/// Keys of the delete confirmation dialog
+fn handle_delete_confirmation(app: &mut App, code: KeyCode) -> AppAction {
+    match code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            let workspace_names: Vec<String> = if app.get_selected_count() > 0 {
+                // Use selected workspaces
+                app.get_selected_workspaces()
+                    .into_iter()
+                    .map(|w| w.name.clone())
+                    .collect()
+            } else if let Some(workspace) = app.get_selected_workspace() {
+                // Use current workspace if none are selected
+                vec![workspace.name.clone()]
+            } else {
+                vec![]
+            };
+
+            if !workspace_names.is_empty() {
+                app.hide_delete_confirmation();
+                return AppAction::DeleteWorkspaces(workspace_names);
+            }
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Char('q') | KeyCode::Esc => {
+            app.hide_delete_confirmation();
+        }
+        _ => {}
+    }
+    AppAction::None
+}
+
+/// Keys while the filter input is open
+fn handle_filter_input(app: &mut App, code: KeyCode) -> AppAction {
+    match code {
+        KeyCode::Esc => app.clear_filter(),
+        KeyCode::Enter => app.finish_filter(),
+        KeyCode::Backspace => app.pop_filter_char(),
+        KeyCode::Down => app.next(),
+        KeyCode::Up => app.previous(),
+        KeyCode::Char(c) => app.push_filter_char(c),
+        _ => {}
+    }
+    AppAction::None
+}
+
+/// Keys while the create form is open
+fn handle_create_form(app: &mut App, code: KeyCode) -> AppAction {
+    match code {
+        KeyCode::Esc => app.hide_create_form(),
+        KeyCode::Enter => {
+            if let Some((task_name, base_ref)) = app.submit_create_form() {
+                return AppAction::CreateWorkspace {
+                    task_name,
+                    base_ref,
+                };
+            }
+        }
+        KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => {
+            if let Some(form) = &mut app.create_form {
+                form.focus = match form.focus {
+                    CreateField::TaskName => CreateField::BaseRef,
+                    CreateField::BaseRef => CreateField::TaskName,
+                };
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(form) = &mut app.create_form {
+                match form.focus {
+                    CreateField::TaskName => form.task_name.pop(),
+                    CreateField::BaseRef => form.base_ref.pop(),
+                };
+            }
+        }
+        KeyCode::Char(c) => {
+            if let Some(form) = &mut app.create_form {
+                match form.focus {
+                    CreateField::TaskName => form.task_name.push(c),
+                    CreateField::BaseRef => form.base_ref.push(c),
                 }
+                form.error = None;
+            }
+        }
+        _ => {}
+    }
+    AppAction::None
+}
+
+/// Keys while the rename form is open
+fn handle_rename_form(app: &mut App, code: KeyCode) -> AppAction {
+    match code {
+        KeyCode::Esc => app.hide_rename_form(),
+        KeyCode::Enter => {
+            if let Some((workspace_name, new_task_name)) = app.submit_rename_form() {
+                return AppAction::RenameWorkspace {
+                    workspace_name,
+                    new_task_name,
+                };
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(form) = &mut app.rename_form {
+                form.new_name.pop();
+            }
+        }
+        KeyCode::Char(c) => {
+            if let Some(form) = &mut app.rename_form {
+                form.new_name.push(c);
+                form.error = None;
             }
-        } else {
-            Ok(AppAction::None)
         }
-    } else {
-        Ok(AppAction::None)
+        _ => {}
     }
+    AppAction::None
+}
+
+/// Keys while the diff viewer is open
+fn handle_diff_view(app: &mut App, code: KeyCode) -> AppAction {
+    match code {
+        KeyCode::Char('q') | KeyCode::Esc => app.hide_diff(),
+        KeyCode::Tab => app.toggle_diff_target(),
+        KeyCode::Down | KeyCode::Char('j') => app.scroll_diff(1),
+        KeyCode::Up | KeyCode::Char('k') => app.scroll_diff(-1),
+        KeyCode::PageDown | KeyCode::Char(' ') => app.scroll_diff(DIFF_PAGE_LINES),
+        KeyCode::PageUp => app.scroll_diff(-DIFF_PAGE_LINES),
+        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('n') => app.select_diff_file(true),
+        KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('p') => app.select_diff_file(false),
+        _ => {}
+    }
+    AppAction::None
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::KeyBindings;
+    use crate::tui::app::{ClickAreas, CreateProgress};
+    use crate::tui::keymap::Keymap;
+    use crate::tui::sort::GroupMode;
     use crate::workspace::WorkspaceInfo;
+    use crossterm::event::KeyModifiers;
+    use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
 
     // Helper function for AppAction testing
     fn create_test_app_with_workspaces() -> App {
@@ -146,11 +408,13 @@ mod tests {
                 name: "test1".to_string(),
                 path: "/path/to/workspace1".to_string(),
                 branch: "work/test1".to_string(),
+                locked: None,
             },
             WorkspaceInfo {
                 name: "test2".to_string(),
                 path: "/path/to/workspace2".to_string(),
                 branch: "work/test2".to_string(),
+                locked: None,
             },
         ];
         app.selected_workspaces = vec![false, false];
@@ -257,6 +521,300 @@ mod tests {
         assert!(!app.is_in_delete_confirmation());
     }
 
+    #[test]
+    fn test_filter_input() {
+        let mut app = create_test_app_with_workspaces();
+        app.start_filter();
+
+        // Typed characters go to the filter, not to the key bindings
+        for c in "2q".chars() {
+            handle_filter_input(&mut app, KeyCode::Char(c));
+        }
+        assert_eq!(app.filter, "2q");
+        assert!(!app.should_quit);
+
+        handle_filter_input(&mut app, KeyCode::Backspace);
+        assert_eq!(app.filter, "2");
+        assert_eq!(app.get_selected_workspace().unwrap().name, "test2");
+
+        // Enter keeps the filter applied
+        handle_filter_input(&mut app, KeyCode::Enter);
+        assert!(!app.is_filtering);
+        assert_eq!(app.filter, "2");
+
+        // Esc clears it
+        app.start_filter();
+        handle_filter_input(&mut app, KeyCode::Esc);
+        assert!(!app.is_filtering);
+        assert!(app.filter.is_empty());
+    }
+
+    #[test]
+    fn test_create_form_input() {
+        let mut app = create_test_app_with_workspaces();
+        app.show_create_form();
+
+        for c in "fix-q".chars() {
+            handle_create_form(&mut app, KeyCode::Char(c));
+        }
+        handle_create_form(&mut app, KeyCode::Tab);
+        for c in "main".chars() {
+            handle_create_form(&mut app, KeyCode::Char(c));
+        }
+        assert!(!app.should_quit);
+
+        assert_eq!(
+            handle_create_form(&mut app, KeyCode::Enter),
+            AppAction::CreateWorkspace {
+                task_name: "fix-q".to_string(),
+                base_ref: Some("main".to_string()),
+            }
+        );
+        assert!(app.is_creating());
+    }
+
+    #[test]
+    fn test_rename_form_input() {
+        let mut app = create_test_app_with_workspaces();
+        app.show_rename_form();
+        assert_eq!(app.rename_form.as_ref().unwrap().new_name, "test1");
+
+        // Unchanged names are rejected and the form stays open
+        assert_eq!(
+            handle_rename_form(&mut app, KeyCode::Enter),
+            AppAction::None
+        );
+        assert!(app.rename_form.as_ref().unwrap().error.is_some());
+
+        for _ in 0..5 {
+            handle_rename_form(&mut app, KeyCode::Backspace);
+        }
+        for c in "login-flow".chars() {
+            handle_rename_form(&mut app, KeyCode::Char(c));
+        }
+        assert!(app.rename_form.as_ref().unwrap().error.is_none());
+        assert_eq!(
+            handle_rename_form(&mut app, KeyCode::Enter),
+            AppAction::RenameWorkspace {
+                workspace_name: "test1".to_string(),
+                new_task_name: "login-flow".to_string(),
+            }
+        );
+        assert!(app.is_in_rename_form());
+
+        app.set_rename_error("Branch already exists".to_string());
+        assert_eq!(
+            app.rename_form.as_ref().unwrap().error.as_deref(),
+            Some("Branch already exists")
+        );
+        handle_rename_form(&mut app, KeyCode::Esc);
+        assert!(!app.is_in_rename_form());
+    }
+
+    fn press(app: &mut App, key: &str) -> AppAction {
+        let key = KeyPress::parse(key).unwrap();
+        handle_key(app, KeyEvent::new(key.code, key.modifiers))
+    }
+
+    #[test]
+    fn test_handle_key_uses_keymap() {
+        let mut app = create_test_app_with_workspaces();
+        let config = [
+            ("delete", KeyBindings::One("D".to_string())),
+            ("diff", KeyBindings::One("g d".to_string())),
+            ("group", KeyBindings::One("g g".to_string())),
+        ]
+        .into_iter()
+        .map(|(name, keys)| (name.to_string(), keys))
+        .collect();
+        app.keymap = Keymap::from_config(&config).unwrap();
+
+        // 'd' is no longer bound
+        assert_eq!(press(&mut app, "d"), AppAction::None);
+        assert!(!app.is_in_delete_confirmation());
+        assert_eq!(press(&mut app, "D"), AppAction::None);
+        assert!(app.is_in_delete_confirmation());
+        assert_eq!(
+            press(&mut app, "y"),
+            AppAction::DeleteWorkspaces(vec!["test1".to_string()])
+        );
+
+        // Chords wait for the next key; Esc cancels them without quitting
+        press(&mut app, "g");
+        assert_eq!(app.pending_keys.len(), 1);
+        assert_eq!(press(&mut app, "esc"), AppAction::None);
+        assert!(app.pending_keys.is_empty());
+        press(&mut app, "g");
+        press(&mut app, "g");
+        assert_eq!(app.group_mode, GroupMode::BranchPrefix);
+
+        // Help overlay closes with any key
+        press(&mut app, "?");
+        assert!(app.show_help);
+        assert_eq!(press(&mut app, "q"), AppAction::None);
+        assert!(!app.show_help);
+
+        assert_eq!(press(&mut app, "R"), AppAction::Refresh);
+
+        press(&mut app, "j");
+        assert_eq!(
+            press(&mut app, "enter"),
+            AppAction::NavigateToWorkspace("/path/to/workspace2".to_string())
+        );
+        assert_eq!(press(&mut app, "esc"), AppAction::Quit);
+    }
+
+    /// Draw the app on a test terminal so that the click areas match the layout
+    fn draw(app: &mut App) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+        let mut click_areas = ClickAreas::default();
+        terminal
+            .draw(|f| click_areas = crate::tui::ui::draw(f, app))
+            .unwrap();
+        app.click_areas = click_areas;
+        terminal.backend().buffer().clone()
+    }
+
+    fn mouse(app: &mut App, kind: MouseEventKind, position: Position) -> AppAction {
+        handle_mouse(
+            app,
+            MouseEvent {
+                kind,
+                column: position.x,
+                row: position.y,
+                modifiers: KeyModifiers::NONE,
+            },
+        )
+    }
+
+    fn click(app: &mut App, position: Position) -> AppAction {
+        mouse(app, MouseEventKind::Down(MouseButton::Left), position)
+    }
+
+    #[test]
+    fn test_mouse_click_and_scroll_list() {
+        let mut app = create_test_app_with_workspaces();
+        draw(&mut app);
+        assert_eq!(app.click_areas.rows.len(), 2);
+
+        // A click selects the row, a second click on it navigates
+        let second_row = app.click_areas.rows[1].0.as_position();
+        assert_eq!(click(&mut app, second_row), AppAction::None);
+        assert_eq!(app.selected_index, 1);
+        assert_eq!(
+            click(&mut app, second_row),
+            AppAction::NavigateToWorkspace("/path/to/workspace2".to_string())
+        );
+
+        // The checkbox toggles the selection instead
+        let buffer = draw(&mut app);
+        let checkbox = app.click_areas.checkboxes[0].0.as_position();
+        assert_eq!(buffer[checkbox].symbol(), "[");
+        click(&mut app, checkbox);
+        assert_eq!(app.selected_workspaces, vec![true, false]);
+        assert_eq!(app.selected_index, 0);
+
+        // The wheel moves the cursor without wrapping around
+        mouse(&mut app, MouseEventKind::ScrollUp, second_row);
+        assert_eq!(app.selected_index, 0);
+        mouse(&mut app, MouseEventKind::ScrollDown, second_row);
+        mouse(&mut app, MouseEventKind::ScrollDown, second_row);
+        assert_eq!(app.selected_index, 1);
+
+        // Clicks outside the rows do nothing
+        assert_eq!(click(&mut app, Position::new(0, 0)), AppAction::None);
+        assert_eq!(app.selected_index, 1);
+    }
+
+    #[test]
+    fn test_mouse_scrolls_create_progress() {
+        let mut app = create_test_app_with_workspaces();
+        app.create_progress = Some(CreateProgress {
+            task_name: "new-task".to_string(),
+            lines: (1..=10).map(|n| format!("line {n}")).collect(),
+            scroll_back: 0,
+            result: None,
+        });
+
+        // The wheel scrolls the output instead of moving the cursor
+        mouse(&mut app, MouseEventKind::ScrollUp, Position::new(0, 0));
+        assert_eq!(app.create_progress.as_ref().unwrap().scroll_back, 3);
+        assert_eq!(app.selected_index, 0);
+        mouse(&mut app, MouseEventKind::ScrollDown, Position::new(0, 0));
+        mouse(&mut app, MouseEventKind::ScrollDown, Position::new(0, 0));
+        assert_eq!(app.create_progress.as_ref().unwrap().scroll_back, 0);
+        assert_eq!(app.selected_index, 0);
+    }
+
+    #[test]
+    fn test_mouse_delete_dialog_buttons() {
+        let mut app = create_test_app_with_workspaces();
+        app.show_delete_confirmation();
+        let buffer = draw(&mut app);
+        let button = |app: &App, wanted: DialogButton| {
+            app.click_areas
+                .buttons
+                .iter()
+                .find(|(_, button)| *button == wanted)
+                .map(|(area, _)| area.as_position())
+                .unwrap()
+        };
+
+        // Rows behind the dialog do not react
+        let row = app.click_areas.rows[1].0.as_position();
+        click(&mut app, row);
+        assert_eq!(app.selected_index, 0);
+
+        let cancel = button(&app, DialogButton::Cancel);
+        assert_eq!(buffer[cancel].symbol(), "[");
+        assert_eq!(buffer[(cancel.x + 1, cancel.y)].symbol(), "N");
+        click(&mut app, cancel);
+        assert!(!app.is_in_delete_confirmation());
+
+        app.show_delete_confirmation();
+        draw(&mut app);
+        let confirm = button(&app, DialogButton::Confirm);
+        assert_eq!(
+            click(&mut app, confirm),
+            AppAction::DeleteWorkspaces(vec!["test1".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_details_dialog_close_keys() {
+        let mut app = create_test_app_with_workspaces();
+        for key in ["enter", "q", "esc"] {
+            app.show_details();
+            press(&mut app, "j");
+            assert!(app.is_in_details_view());
+            assert_eq!(app.selected_index, 0);
+            assert_eq!(press(&mut app, key), AppAction::None);
+            assert!(!app.is_in_details_view());
+        }
+    }
+
+    #[test]
+    fn test_locked_workspace_not_deleted() {
+        let mut app = create_test_app_with_workspaces();
+        assert_eq!(
+            press(&mut app, "l"),
+            AppAction::ToggleLock("test1".to_string())
+        );
+        app.set_locked("test1", Some("agent running".to_string()));
+
+        // The delete dialog is not opened for a locked workspace, also as part of a selection
+        assert_eq!(press(&mut app, "d"), AppAction::None);
+        assert!(!app.is_in_delete_confirmation());
+        assert!(app.message.as_deref().unwrap().contains("test1 is locked"));
+        app.toggle_all_selection();
+        press(&mut app, "d");
+        assert!(!app.is_in_delete_confirmation());
+
+        app.set_locked("test1", None);
+        press(&mut app, "d");
+        assert!(app.is_in_delete_confirmation());
+    }
+
     #[test]
     fn test_delete_workspace_action() {
         let app = create_test_app_with_workspaces();
@@ -0,0 +1,618 @@
+use crate::config::KeyBindings;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Action of the workspace list that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Down,
+    Up,
+    Navigate,
+    ToggleSelect,
+    SelectAll,
+    Filter,
+    Sort,
+    Group,
+    Preview,
+    Diff,
+    New,
+    Rename,
+    Delete,
+    Lock,
+    Details,
+    Refresh,
+    OpenEditor,
+    OpenTerminal,
+    OpenCustom,
+    Help,
+}
+
+impl Action {
+    /// All actions in the order of the help overlay
+    pub const ALL: [Action; 21] = [
+        Action::Down,
+        Action::Up,
+        Action::Navigate,
+        Action::ToggleSelect,
+        Action::SelectAll,
+        Action::Filter,
+        Action::Sort,
+        Action::Group,
+        Action::Preview,
+        Action::Diff,
+        Action::New,
+        Action::Rename,
+        Action::Delete,
+        Action::Lock,
+        Action::Details,
+        Action::Refresh,
+        Action::OpenEditor,
+        Action::OpenTerminal,
+        Action::OpenCustom,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// Name of the action in the keymap section of the configuration file
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Down => "down",
+            Action::Up => "up",
+            Action::Navigate => "navigate",
+            Action::ToggleSelect => "toggle-select",
+            Action::SelectAll => "select-all",
+            Action::Filter => "filter",
+            Action::Sort => "sort",
+            Action::Group => "group",
+            Action::Preview => "preview",
+            Action::Diff => "diff",
+            Action::New => "new",
+            Action::Rename => "rename",
+            Action::Delete => "delete",
+            Action::Lock => "lock",
+            Action::Details => "details",
+            Action::Refresh => "refresh",
+            Action::OpenEditor => "open-editor",
+            Action::OpenTerminal => "open-terminal",
+            Action::OpenCustom => "open-custom",
+            Action::Help => "help",
+        }
+    }
+
+    pub fn from_config_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.config_name() == name)
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Quit => "Quit (Esc clears an active filter first)",
+            Action::Down => "Move down",
+            Action::Up => "Move up",
+            Action::Navigate => "Open the workspace (print its path and exit)",
+            Action::ToggleSelect => "Toggle selection of the workspace",
+            Action::SelectAll => "Select/deselect all visible workspaces",
+            Action::Filter => "Filter workspaces",
+            Action::Sort => "Cycle sort order",
+            Action::Group => "Toggle grouping by branch prefix",
+            Action::Preview => "Show/hide the preview pane",
+            Action::Diff => "View the workspace diff",
+            Action::New => "Create a new workspace",
+            Action::Rename => "Rename the workspace",
+            Action::Delete => "Delete selected workspace(s)",
+            Action::Lock => "Lock/unlock the workspace against deletion",
+            Action::Details => "Show workspace details",
+            Action::Refresh => "Reload the list and recalculate sizes",
+            Action::OpenEditor => "Open in the editor",
+            Action::OpenTerminal => "Open in a new terminal",
+            Action::OpenCustom => "Run the custom open command",
+            Action::Help => "Show this help",
+        }
+    }
+
+    fn default_keys(self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "esc"],
+            Action::Down => &["down", "j"],
+            Action::Up => &["up", "k"],
+            Action::Navigate => &["enter"],
+            Action::ToggleSelect => &["space"],
+            Action::SelectAll => &["a"],
+            Action::Filter => &["/"],
+            Action::Sort => &["s"],
+            Action::Group => &["g"],
+            Action::Preview => &["p"],
+            Action::Diff => &["v"],
+            Action::New => &["n"],
+            Action::Rename => &["r"],
+            Action::Delete => &["d"],
+            Action::Lock => &["l"],
+            Action::Details => &["i"],
+            Action::Refresh => &["R"],
+            Action::OpenEditor => &["e"],
+            Action::OpenTerminal => &["t"],
+            Action::OpenCustom => &["o"],
+            Action::Help => &["?"],
+        }
+    }
+}
+
+/// Entries of the footer help line: actions shown together and their label
+const FOOTER: &[(&[Action], &str)] = &[
+    (&[Action::Up, Action::Down], "Select"),
+    (&[Action::ToggleSelect], "Multi-select"),
+    (&[Action::SelectAll], "All"),
+    (&[Action::Filter], "Filter"),
+    (&[Action::Sort], "Sort"),
+    (&[Action::Group], "Group"),
+    (&[Action::Preview], "Preview"),
+    (&[Action::Diff], "Diff"),
+    (&[Action::New], "New"),
+    (&[Action::Rename], "Rename"),
+    (
+        &[Action::OpenEditor, Action::OpenTerminal, Action::OpenCustom],
+        "Open",
+    ),
+    (&[Action::Delete], "Delete"),
+    (&[Action::Details], "Details"),
+    (&[Action::Help], "Help"),
+    (&[Action::Quit], "Quit"),
+];
+
+/// A key with its modifiers. Shift is folded into the character for printable keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyPress {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            // 'D' already says shift, and terminals disagree on whether they report it
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self {
+            code,
+            modifiers: modifiers
+                & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT),
+        }
+    }
+
+    /// Parse a key such as "j", "D", "enter", "ctrl-d" or "shift-tab"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        // A single character is always a key, even '-'
+        while rest.chars().count() > 1 {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl-") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt-") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift-") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => {
+                if modifiers.contains(KeyModifiers::SHIFT) {
+                    modifiers -= KeyModifiers::SHIFT;
+                    KeyCode::Char(c.to_ascii_uppercase())
+                } else {
+                    KeyCode::Char(c)
+                }
+            }
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                    modifiers -= KeyModifiers::SHIFT;
+                    KeyCode::BackTab
+                }
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("Unknown key: {text}")),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl From<KeyEvent> for KeyPress {
+    fn from(event: KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift-Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Delete => write!(f, "Del"),
+            KeyCode::Insert => write!(f, "Ins"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => write!(f, "{code:?}"),
+        }
+    }
+}
+
+/// Sequence of keys pressed one after another (a single key for most bindings)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding(pub Vec<KeyPress>);
+
+impl KeyBinding {
+    /// Parse space separated keys, e.g. "g d"
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let keys = text
+            .split_whitespace()
+            .map(KeyPress::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err("Empty key binding".to_string());
+        }
+        Ok(Self(keys))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<String> = self.0.iter().map(KeyPress::to_string).collect();
+        write!(f, "{}", keys.join(" "))
+    }
+}
+
+/// Result of looking up the keys pressed so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMatch {
+    Action(Action),
+    /// Start of a chord; wait for the next key
+    Pending,
+    Unbound,
+}
+
+/// Keys of the workspace list actions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .map(|key| KeyBinding::parse(key).expect("default key binding is valid"))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Default keymap with the keys from the configuration file applied
+    pub fn from_config(keymap: &BTreeMap<String, KeyBindings>) -> Result<Self, String> {
+        let mut result = Self::default();
+        for (name, keys) in keymap {
+            let action = Action::from_config_name(name)
+                .ok_or_else(|| format!("Unknown keymap action: {name}"))?;
+            let keys = keys
+                .keys()
+                .into_iter()
+                .map(|key| KeyBinding::parse(key).map_err(|e| format!("{name}: {e}")))
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some((_, bindings)) = result.bindings.iter_mut().find(|(a, _)| *a == action) {
+                *bindings = keys;
+            }
+        }
+        result.validate()?;
+        Ok(result)
+    }
+
+    /// Every key sequence must lead to exactly one action
+    fn validate(&self) -> Result<(), String> {
+        let all: Vec<(Action, &KeyBinding)> = self
+            .bindings
+            .iter()
+            .flat_map(|(action, keys)| keys.iter().map(move |key| (*action, key)))
+            .collect();
+        for (i, (action, key)) in all.iter().enumerate() {
+            for (other_action, other_key) in &all[i + 1..] {
+                if key == other_key {
+                    return Err(format!(
+                        "'{key}' is bound to both {} and {}",
+                        action.config_name(),
+                        other_action.config_name()
+                    ));
+                }
+                if key.0.starts_with(&other_key.0) || other_key.0.starts_with(&key.0) {
+                    return Err(format!(
+                        "'{key}' ({}) and '{other_key}' ({}) overlap; one starts with the other",
+                        action.config_name(),
+                        other_action.config_name()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Look up the keys pressed so far (more than one while typing a chord)
+    pub fn resolve(&self, pressed: &[KeyPress]) -> KeyMatch {
+        let mut pending = false;
+        for (action, keys) in &self.bindings {
+            for key in keys {
+                if key.0 == pressed {
+                    return KeyMatch::Action(*action);
+                }
+                if key.0.starts_with(pressed) {
+                    pending = true;
+                }
+            }
+        }
+        if pending {
+            KeyMatch::Pending
+        } else {
+            KeyMatch::Unbound
+        }
+    }
+
+    /// Footer help text, e.g. "↑/↓: Select  /: Filter  q: Quit", cut to `width` columns.
+    /// Entries that don't fit are left out, but the help entry is always kept.
+    pub fn help_line(&self, width: usize) -> String {
+        let entries: Vec<(bool, String)> = FOOTER
+            .iter()
+            .filter_map(|(actions, label)| {
+                let keys: Vec<String> = actions
+                    .iter()
+                    .filter_map(|action| self.keys(*action).first())
+                    .map(KeyBinding::to_string)
+                    .collect();
+                (!keys.is_empty()).then(|| {
+                    let is_help = actions.contains(&Action::Help);
+                    (is_help, format!("{}: {label}", keys.join("/")))
+                })
+            })
+            .collect();
+
+        let help = entries.iter().find(|(is_help, _)| *is_help);
+        let mut remaining =
+            width.saturating_sub(help.map_or(0, |(_, text)| text.chars().count() + 2));
+        let mut shown = Vec::new();
+        let mut full = false;
+        for (is_help, text) in &entries {
+            let len = text.chars().count() + 2;
+            if *is_help {
+                shown.push(text.as_str());
+            } else if !full && len <= remaining {
+                remaining -= len;
+                shown.push(text.as_str());
+            } else {
+                full = true;
+            }
+        }
+        shown.join("  ")
+    }
+
+    /// Rows of the help overlay: all keys of an action and its description
+    pub fn help_entries(&self) -> Vec<(String, &'static str)> {
+        Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys: Vec<String> = self
+                    .keys(action)
+                    .iter()
+                    .map(KeyBinding::to_string)
+                    .collect();
+                let keys = if keys.is_empty() {
+                    "(unbound)".to_string()
+                } else {
+                    keys.join(", ")
+                };
+                (keys, action.description())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> KeyPress {
+        KeyPress::parse(text).unwrap()
+    }
+
+    fn keymap(entries: &[(&str, &[&str])]) -> Result<Keymap, String> {
+        let config = entries
+            .iter()
+            .map(|(name, keys)| {
+                (
+                    name.to_string(),
+                    KeyBindings::Many(keys.iter().map(|k| k.to_string()).collect()),
+                )
+            })
+            .collect();
+        Keymap::from_config(&config)
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            key("j"),
+            KeyPress::new(KeyCode::Char('j'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            key("-"),
+            KeyPress::new(KeyCode::Char('-'), KeyModifiers::NONE)
+        );
+        assert_eq!(
+            key("ctrl-d"),
+            KeyPress::new(KeyCode::Char('d'), KeyModifiers::CONTROL)
+        );
+        assert_eq!(
+            key("Ctrl-Alt-x"),
+            KeyPress::new(
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )
+        );
+        // Shift is part of the character, as terminals report it
+        assert_eq!(key("shift-d"), key("D"));
+        assert_eq!(
+            KeyPress::from(KeyEvent::new(KeyCode::Char('D'), KeyModifiers::SHIFT)),
+            key("D")
+        );
+        assert_eq!(
+            key("shift-tab"),
+            KeyPress::new(KeyCode::BackTab, KeyModifiers::NONE)
+        );
+        assert_eq!(key("space").code, KeyCode::Char(' '));
+        assert_eq!(key("F5").code, KeyCode::F(5));
+        assert!(KeyPress::parse("hyper-x").is_err());
+        assert!(KeyPress::parse("f13").is_err());
+
+        assert_eq!(key("ctrl-d").to_string(), "Ctrl-d");
+        assert_eq!(KeyBinding::parse("g  d").unwrap().to_string(), "g d");
+        assert!(KeyBinding::parse(" ").is_err());
+    }
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = Keymap::default();
+        assert!(keymap.validate().is_ok());
+        assert_eq!(
+            keymap.resolve(&[key("d")]),
+            KeyMatch::Action(Action::Delete)
+        );
+        assert_eq!(
+            keymap.resolve(&[key("down")]),
+            KeyMatch::Action(Action::Down)
+        );
+        assert_eq!(keymap.resolve(&[key("x")]), KeyMatch::Unbound);
+        assert_eq!(
+            keymap.help_line(usize::MAX),
+            "↑/↓: Select  Space: Multi-select  a: All  /: Filter  s: Sort  g: Group  p: Preview  v: Diff  n: New  r: Rename  e/t/o: Open  d: Delete  i: Details  ?: Help  q: Quit"
+        );
+        // Narrow terminals drop entries but keep the help entry
+        let narrow = keymap.help_line(40);
+        assert_eq!(narrow, "↑/↓: Select  ?: Help");
+        assert!(narrow.chars().count() <= 40);
+    }
+
+    #[test]
+    fn test_configured_keymap_with_chords() {
+        let keymap = keymap(&[
+            ("delete", &["D"]),
+            ("diff", &["g d"]),
+            ("group", &["g g"]),
+            ("quit", &["q", "ctrl-c"]),
+            ("details", &[]),
+        ])
+        .unwrap();
+
+        assert_eq!(keymap.resolve(&[key("d")]), KeyMatch::Unbound);
+        assert_eq!(
+            keymap.resolve(&[key("D")]),
+            KeyMatch::Action(Action::Delete)
+        );
+        assert_eq!(keymap.resolve(&[key("g")]), KeyMatch::Pending);
+        assert_eq!(
+            keymap.resolve(&[key("g"), key("d")]),
+            KeyMatch::Action(Action::Diff)
+        );
+        assert_eq!(
+            keymap.resolve(&[key("ctrl-c")]),
+            KeyMatch::Action(Action::Quit)
+        );
+        assert_eq!(keymap.resolve(&[key("i")]), KeyMatch::Unbound);
+
+        // Footer and help follow the keymap
+        let help = keymap.help_line(usize::MAX);
+        assert!(help.contains("g d: Diff"));
+        assert!(help.contains("D: Delete"));
+        assert!(!help.contains("Details"));
+        assert!(keymap
+            .help_entries()
+            .contains(&("q, Ctrl-c".to_string(), Action::Quit.description())));
+    }
+
+    #[test]
+    fn test_invalid_keymap() {
+        assert!(keymap(&[("launch", &["l"])])
+            .unwrap_err()
+            .contains("Unknown keymap action"));
+        assert!(keymap(&[("delete", &["hyper-x"])]).is_err());
+        // 'd' is still bound to delete
+        assert_eq!(
+            keymap(&[("diff", &["d"])]).unwrap_err(),
+            "'d' is bound to both diff and delete"
+        );
+        // 'g' would never reach the "g d" chord
+        assert!(keymap(&[("diff", &["g d"])])
+            .unwrap_err()
+            .contains("overlap"));
+    }
+}
@@ -0,0 +1,295 @@
+use crate::diff::{DiffTarget, WorkspaceDiff};
+use crate::error::GitwsResult;
+use crate::size::{SizeCache, SizeState};
+use crate::workspace::{
+    BranchTracking, WorkspaceDetails, WorkspaceInfo, WorkspaceManager, WorkspaceStatus,
+};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Maximum number of worker threads
+const MAX_WORKERS: usize = 4;
+
+/// Data loaded in the background, shown as a spinner until it arrives
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadState<T> {
+    Loading,
+    Loaded(T),
+    Failed(String),
+}
+
+/// Information shown in a list row
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceSummary {
+    pub status: Option<WorkspaceStatus>,
+    pub tracking: BranchTracking,
+    /// Unix time of the latest activity, used for sorting
+    pub last_activity: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum LoadRequest {
+    Summary(WorkspaceInfo),
+    Details(WorkspaceInfo),
+    /// Directory size only, needed when sorting by size
+    Size(WorkspaceInfo),
+    Diff(WorkspaceInfo, DiffTarget),
+}
+
+#[derive(Debug)]
+pub enum LoadResult {
+    Summary {
+        name: String,
+        summary: WorkspaceSummary,
+    },
+    Details {
+        name: String,
+        details: Result<Box<WorkspaceDetails>, String>,
+    },
+    Size {
+        name: String,
+        size: SizeState,
+    },
+    Diff {
+        name: String,
+        target: DiffTarget,
+        diff: Result<WorkspaceDiff, String>,
+    },
+}
+
+#[derive(Default)]
+struct JobQueue {
+    jobs: VecDeque<LoadRequest>,
+    closed: bool,
+}
+
+type SharedQueue = Arc<(Mutex<JobQueue>, Condvar)>;
+
+/// Pool of worker threads loading workspace information off the UI thread
+pub struct Loader {
+    queue: SharedQueue,
+    results: Receiver<LoadResult>,
+}
+
+impl Loader {
+    pub fn new(workspace_manager: &WorkspaceManager) -> GitwsResult<Self> {
+        let queue: SharedQueue = Arc::default();
+        let (sender, results) = mpsc::channel();
+
+        let workers = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(MAX_WORKERS);
+        debug!("Starting {} loader workers", workers);
+
+        for _ in 0..workers {
+            let manager = workspace_manager.try_clone()?;
+            let size_cache = workspace_manager.size_cache();
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            thread::spawn(move || worker(manager, size_cache, queue, sender));
+        }
+
+        Ok(Self { queue, results })
+    }
+
+    /// Queue a request; details are requested by the user and go before list rows
+    pub fn request(&self, request: LoadRequest) {
+        let (lock, condvar) = &*self.queue;
+        let mut queue = lock.lock().unwrap();
+        match request {
+            LoadRequest::Details(_) | LoadRequest::Diff(..) => queue.jobs.push_front(request),
+            LoadRequest::Summary(_) | LoadRequest::Size(_) => queue.jobs.push_back(request),
+        }
+        condvar.notify_one();
+    }
+
+    /// Results that arrived since the last call
+    pub fn try_results(&self) -> Vec<LoadResult> {
+        self.results.try_iter().collect()
+    }
+}
+
+impl Drop for Loader {
+    fn drop(&mut self) {
+        let (lock, condvar) = &*self.queue;
+        lock.lock().unwrap().closed = true;
+        condvar.notify_all();
+    }
+}
+
+fn worker(
+    manager: WorkspaceManager,
+    size_cache: SizeCache,
+    queue: SharedQueue,
+    sender: Sender<LoadResult>,
+) {
+    loop {
+        let request = {
+            let (lock, condvar) = &*queue;
+            let mut queue = lock.lock().unwrap();
+            loop {
+                if queue.closed {
+                    return;
+                }
+                if let Some(request) = queue.jobs.pop_front() {
+                    break request;
+                }
+                queue = condvar.wait(queue).unwrap();
+            }
+        };
+
+        let result = match request {
+            LoadRequest::Summary(workspace) => LoadResult::Summary {
+                summary: WorkspaceSummary {
+                    status: manager.get_workspace_status(&workspace),
+                    tracking: manager.get_branch_tracking(&workspace),
+                    last_activity: manager.get_last_activity(&workspace),
+                },
+                name: workspace.name,
+            },
+            LoadRequest::Details(workspace) => {
+                let details = manager
+                    .get_workspace_details(&workspace)
+                    .map(Box::new)
+                    .map_err(|e| e.to_string());
+                if details.as_ref().is_ok_and(|d| d.disk_usage.is_none()) {
+                    wait_for_size(
+                        size_cache.clone(),
+                        workspace.name.clone(),
+                        PathBuf::from(&workspace.path),
+                        sender.clone(),
+                    );
+                }
+                LoadResult::Details {
+                    name: workspace.name,
+                    details,
+                }
+            }
+            LoadRequest::Diff(workspace, target) => LoadResult::Diff {
+                diff: manager
+                    .get_workspace_diff(&workspace, target)
+                    .map_err(|e| e.to_string()),
+                name: workspace.name,
+                target,
+            },
+            LoadRequest::Size(workspace) => {
+                // Reported by the waiting thread once calculated
+                wait_for_size(
+                    size_cache.clone(),
+                    workspace.name,
+                    PathBuf::from(&workspace.path),
+                    sender.clone(),
+                );
+                continue;
+            }
+        };
+
+        if sender.send(result).is_err() {
+            // TUI has exited
+            return;
+        }
+    }
+}
+
+/// Report the directory size once the background calculation finishes,
+/// without holding up a worker
+///
+/// The first completed result is reported even if the directory changed
+/// meanwhile, so a busy directory does not restart the walk forever.
+fn wait_for_size(cache: SizeCache, name: String, path: PathBuf, sender: Sender<LoadResult>) {
+    if !path.exists() {
+        return;
+    }
+    thread::spawn(move || {
+        let mut size = cache.get(&path);
+        while size == SizeState::Calculating {
+            thread::sleep(Duration::from_millis(100));
+            match cache.peek(&path) {
+                Some(state) => size = state,
+                None => return,
+            }
+        }
+        if let SizeState::Failed(e) = &size {
+            warn!("Failed to calculate size of {}: {}", name, e);
+        }
+        let _ = sender.send(LoadResult::Size { name, size });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn collect_results(loader: &Loader, count: usize) -> Vec<LoadResult> {
+        let started = Instant::now();
+        let mut results = Vec::new();
+        while results.len() < count && started.elapsed() < Duration::from_secs(10) {
+            results.extend(loader.try_results());
+            thread::sleep(Duration::from_millis(10));
+        }
+        results
+    }
+
+    #[test]
+    fn test_loader_loads_summaries_and_details() {
+        if let Ok(manager) = WorkspaceManager::new() {
+            let loader = Loader::new(&manager).unwrap();
+            let workspace = WorkspaceInfo {
+                name: "current".to_string(),
+                path: ".".to_string(),
+                branch: "test/branch".to_string(),
+                locked: None,
+            };
+
+            loader.request(LoadRequest::Summary(workspace.clone()));
+            loader.request(LoadRequest::Details(workspace));
+
+            let results = collect_results(&loader, 2);
+            assert_eq!(results.len(), 2);
+            for result in results {
+                match result {
+                    LoadResult::Summary { name, summary } => {
+                        assert_eq!(name, "current");
+                        assert!(summary.status.is_some());
+                        assert!(summary.last_activity.is_some());
+                    }
+                    LoadResult::Details { name, details } => {
+                        assert_eq!(name, "current");
+                        assert!(details.is_ok());
+                    }
+                    LoadResult::Size { .. } | LoadResult::Diff { .. } => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_loader_missing_workspace() {
+        if let Ok(manager) = WorkspaceManager::new() {
+            let loader = Loader::new(&manager).unwrap();
+            loader.request(LoadRequest::Summary(WorkspaceInfo {
+                name: "missing".to_string(),
+                path: "/path/that/does/not/exist".to_string(),
+                branch: "test/branch".to_string(),
+                locked: None,
+            }));
+
+            let results = collect_results(&loader, 1);
+            match &results[..] {
+                [LoadResult::Summary { summary, .. }] => {
+                    assert!(summary.status.is_none());
+                    assert_eq!(summary.tracking, BranchTracking::default());
+                    assert!(summary.last_activity.is_none());
+                }
+                other => panic!("unexpected results: {other:?}"),
+            }
+        }
+    }
+}
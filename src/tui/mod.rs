@@ -1,5 +1,12 @@
 pub mod app;
+pub mod create;
 pub mod events;
+pub mod keymap;
+pub mod loader;
+pub mod open;
+pub mod picker;
+pub mod sort;
+pub mod theme;
 pub mod ui;
 
 pub use app::App;
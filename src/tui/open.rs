@@ -0,0 +1,210 @@
+use crate::config::{OpenCommand, OpenSettings};
+use crate::error::{GitwsError, GitwsResult};
+use crate::workspace::{build_workspace_command, WorkspaceInfo};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::process::Stdio;
+use std::thread;
+use tracing::{debug, error};
+
+/// Program the highlighted workspace is opened in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenTarget {
+    Editor,
+    Terminal,
+    Custom,
+}
+
+impl OpenTarget {
+    pub fn label(self) -> &'static str {
+        match self {
+            OpenTarget::Editor => "editor",
+            OpenTarget::Terminal => "terminal",
+            OpenTarget::Custom => "custom command",
+        }
+    }
+
+    /// Key of the action under `tui.open` in the configuration file
+    pub fn config_key(self) -> &'static str {
+        match self {
+            OpenTarget::Editor => "editor",
+            OpenTarget::Terminal => "terminal",
+            OpenTarget::Custom => "custom",
+        }
+    }
+
+    /// Configured command, or the built-in default for the current environment
+    pub fn command(self, settings: &OpenSettings) -> Option<OpenCommand> {
+        self.command_with_env(settings, |name| std::env::var(name).ok())
+    }
+
+    fn command_with_env(
+        self,
+        settings: &OpenSettings,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Option<OpenCommand> {
+        let configured = match self {
+            OpenTarget::Editor => &settings.editor,
+            OpenTarget::Terminal => &settings.terminal,
+            OpenTarget::Custom => &settings.custom,
+        };
+        if configured.is_some() {
+            return configured.clone();
+        }
+
+        match self {
+            OpenTarget::Editor => Some(
+                // An empty variable counts as unset
+                match env("VISUAL")
+                    .filter(|editor| !editor.is_empty())
+                    .or_else(|| env("EDITOR").filter(|editor| !editor.is_empty()))
+                {
+                    // Usually a terminal editor, which needs the terminal to itself
+                    Some(editor) => OpenCommand {
+                        command: format!("{editor} ."),
+                        wait: true,
+                    },
+                    None => OpenCommand {
+                        command: "code .".to_string(),
+                        wait: false,
+                    },
+                },
+            ),
+            OpenTarget::Terminal => env("TMUX").map(|_| OpenCommand {
+                command: r#"tmux new-window -c "$GITWS_WORKSPACE_PATH" -n "$GITWS_TASK""#
+                    .to_string(),
+                wait: false,
+            }),
+            OpenTarget::Custom => None,
+        }
+    }
+}
+
+/// Start a command in the background without attaching it to the TUI's terminal
+pub fn spawn_open(
+    workspace: &WorkspaceInfo,
+    command: &OpenCommand,
+    env: &BTreeMap<String, String>,
+) -> GitwsResult<()> {
+    debug!("Opening {} with: {}", workspace.name, command.command);
+    let mut child = build_workspace_command(workspace, &[command.command.clone()], env)?
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            error!("Failed to start command: {} - {}", command.command, e);
+            GitwsError::workspace(format!("Command execution error: {e}"))
+        })?;
+
+    // Reap the process when it exits
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Run a command on the controlling terminal and wait for it. The TUI draws on /dev/tty, so
+/// an editor must too: gitws's own stdout may be captured by `dir=$(gitws list)`.
+pub fn run_on_terminal(
+    workspace: &WorkspaceInfo,
+    command: &OpenCommand,
+    env: &BTreeMap<String, String>,
+) -> GitwsResult<()> {
+    debug!("Running {} in {}", command.command, workspace.name);
+    let mut cmd = build_workspace_command(workspace, &[command.command.clone()], env)?;
+    match OpenOptions::new().read(true).write(true).open("/dev/tty") {
+        Ok(tty) => {
+            let stdin = tty
+                .try_clone()
+                .map_err(|e| GitwsError::io(format!("{e}")))?;
+            let stdout = tty
+                .try_clone()
+                .map_err(|e| GitwsError::io(format!("{e}")))?;
+            cmd.stdin(stdin).stdout(stdout).stderr(tty);
+        }
+        Err(e) => debug!("Cannot open /dev/tty ({}), using the current handles", e),
+    }
+    cmd.status().map(|_| ()).map_err(|e| {
+        error!("Failed to execute command: {} - {}", command.command, e);
+        GitwsError::workspace(format!("Command execution error: {e}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_of<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_open_command_defaults() {
+        let settings = OpenSettings::default();
+
+        let editor = OpenTarget::Editor.command_with_env(&settings, env_of(&[("EDITOR", "vim")]));
+        assert_eq!(
+            editor,
+            Some(OpenCommand {
+                command: "vim .".to_string(),
+                wait: true,
+            })
+        );
+        let editor = OpenTarget::Editor
+            .command_with_env(&settings, env_of(&[("VISUAL", "hx"), ("EDITOR", "vim")]));
+        assert_eq!(editor.unwrap().command, "hx .");
+        let editor = OpenTarget::Editor
+            .command_with_env(&settings, env_of(&[("VISUAL", ""), ("EDITOR", "vim")]));
+        assert_eq!(editor.unwrap().command, "vim .");
+        let editor = OpenTarget::Editor
+            .command_with_env(&settings, env_of(&[]))
+            .unwrap();
+        assert_eq!(editor.command, "code .");
+        assert!(!editor.wait);
+
+        // A terminal is only opened by default inside tmux
+        assert!(OpenTarget::Terminal
+            .command_with_env(&settings, env_of(&[]))
+            .is_none());
+        let terminal = OpenTarget::Terminal
+            .command_with_env(&settings, env_of(&[("TMUX", "/tmp/tmux-0/default")]))
+            .unwrap();
+        assert!(terminal.command.starts_with("tmux new-window"));
+
+        assert!(OpenTarget::Custom
+            .command_with_env(&settings, env_of(&[]))
+            .is_none());
+    }
+
+    #[test]
+    fn test_open_command_configured() {
+        let custom = OpenCommand {
+            command: "lazygit".to_string(),
+            wait: true,
+        };
+        let settings = OpenSettings {
+            editor: Some(OpenCommand {
+                command: "zed .".to_string(),
+                wait: false,
+            }),
+            custom: Some(custom.clone()),
+            ..OpenSettings::default()
+        };
+
+        let env = env_of(&[("EDITOR", "vim")]);
+        assert_eq!(
+            OpenTarget::Editor
+                .command_with_env(&settings, &env)
+                .unwrap()
+                .command,
+            "zed ."
+        );
+        assert_eq!(
+            OpenTarget::Custom.command_with_env(&settings, &env),
+            Some(custom)
+        );
+    }
+}
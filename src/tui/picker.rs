@@ -0,0 +1,206 @@
+use crate::tui::theme::Theme;
+use crate::workspace::WorkspaceInfo;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Most workspaces shown at once; the list scrolls past this
+pub const PICKER_MAX_ROWS: u16 = 10;
+
+#[derive(Debug)]
+pub enum PickerAction {
+    None,
+    Cancel,
+    Select(WorkspaceInfo),
+}
+
+/// Compact list for choosing between the workspaces a `gitws cd` query matched
+pub struct Picker {
+    pub query: String,
+    pub workspaces: Vec<WorkspaceInfo>,
+    pub selected: usize,
+}
+
+impl Picker {
+    pub fn new(query: &str, workspaces: Vec<WorkspaceInfo>) -> Self {
+        Self {
+            query: query.to_string(),
+            workspaces,
+            selected: 0,
+        }
+    }
+
+    /// Lines the picker needs: the prompt and the visible rows
+    pub fn height(&self) -> u16 {
+        (self.workspaces.len() as u16).min(PICKER_MAX_ROWS) + 1
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> PickerAction {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => PickerAction::Cancel,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                PickerAction::Cancel
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                PickerAction::None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.workspaces.len().saturating_sub(1));
+                PickerAction::None
+            }
+            KeyCode::Enter => self.select(self.selected),
+            // 1-9 pick one of the first rows directly
+            KeyCode::Char(c @ '1'..='9') => self.select(c as usize - '1' as usize),
+            _ => PickerAction::None,
+        }
+    }
+
+    fn select(&self, index: usize) -> PickerAction {
+        match self.workspaces.get(index) {
+            Some(workspace) => PickerAction::Select(workspace.clone()),
+            None => PickerAction::None,
+        }
+    }
+
+    pub fn draw(&self, f: &mut Frame, theme: &Theme) {
+        let area = f.area();
+        let prompt = Line::from(vec![
+            Span::styled(
+                format!(
+                    "{} workspaces match '{}'",
+                    self.workspaces.len(),
+                    self.query
+                ),
+                Style::default().fg(theme.accent),
+            ),
+            Span::styled(
+                "  ↑/↓ move  Enter/1-9 pick  Esc cancel",
+                Style::default().fg(theme.muted),
+            ),
+        ]);
+        f.render_widget(Paragraph::new(prompt), Rect { height: 1, ..area });
+
+        let items: Vec<ListItem> = self
+            .workspaces
+            .iter()
+            .enumerate()
+            .map(|(i, workspace)| {
+                let number = if i < 9 {
+                    format!("{} ", i + 1)
+                } else {
+                    "  ".to_string()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(number, Style::default().fg(theme.muted)),
+                    Span::styled(workspace.task_name(), Style::default().fg(theme.text)),
+                    Span::raw("  "),
+                    Span::styled(workspace.branch.as_str(), Style::default().fg(theme.muted)),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(theme.selected_style())
+            .highlight_symbol("→ ");
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        f.render_stateful_widget(
+            list,
+            Rect {
+                y: area.y + 1,
+                height: area.height.saturating_sub(1),
+                ..area
+            },
+            &mut state,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn picker() -> Picker {
+        Picker::new(
+            "auth",
+            (1..=12)
+                .map(|i| WorkspaceInfo {
+                    name: format!("20250101-1200{i:02}-auth-{i}"),
+                    path: format!("/tmp/ws/auth-{i}"),
+                    branch: format!("work/auth-{i}"),
+                    locked: None,
+                })
+                .collect(),
+        )
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_picker_keys() {
+        let mut picker = picker();
+        assert_eq!(picker.height(), PICKER_MAX_ROWS + 1);
+
+        assert!(matches!(
+            picker.handle_key(key(KeyCode::Up)),
+            PickerAction::None
+        ));
+        assert_eq!(picker.selected, 0);
+        picker.handle_key(key(KeyCode::Down));
+        picker.handle_key(key(KeyCode::Char('j')));
+        match picker.handle_key(key(KeyCode::Enter)) {
+            PickerAction::Select(workspace) => assert_eq!(workspace.branch, "work/auth-3"),
+            action => panic!("Expected a selection, got {action:?}"),
+        }
+        match picker.handle_key(key(KeyCode::Char('9'))) {
+            PickerAction::Select(workspace) => assert_eq!(workspace.branch, "work/auth-9"),
+            action => panic!("Expected a selection, got {action:?}"),
+        }
+
+        // The selection stops at the last row
+        for _ in 0..20 {
+            picker.handle_key(key(KeyCode::Down));
+        }
+        assert_eq!(picker.selected, 11);
+
+        assert!(matches!(
+            picker.handle_key(key(KeyCode::Esc)),
+            PickerAction::Cancel
+        ));
+        assert!(matches!(
+            picker.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            PickerAction::Cancel
+        ));
+    }
+
+    #[test]
+    fn test_picker_draw() {
+        let mut picker = picker();
+        picker.selected = 11;
+        let mut terminal = Terminal::new(TestBackend::new(60, picker.height())).unwrap();
+        terminal
+            .draw(|f| picker.draw(f, &Theme::default()))
+            .unwrap();
+        let lines: Vec<String> = terminal
+            .backend()
+            .buffer()
+            .content
+            .chunks(60)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+            .collect();
+        assert!(lines[0].starts_with("12 workspaces match 'auth'"));
+        // Scrolled so that the selected row is visible
+        assert!(lines
+            .last()
+            .unwrap()
+            .starts_with("→   auth-12  work/auth-12"));
+        assert!(!lines.iter().any(|line| line.contains("1 auth-1 ")));
+    }
+}
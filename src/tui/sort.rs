@@ -0,0 +1,115 @@
+use crate::workspace::WorkspaceInfo;
+use serde::{Deserialize, Serialize};
+
+/// Order of the workspace list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    /// Oldest first (creation timestamp in the workspace name)
+    #[default]
+    Created,
+    /// Most recently used first
+    LastActivity,
+    /// Task name
+    Name,
+    /// Largest first
+    Size,
+    /// Workspaces with uncommitted changes first
+    DirtyFirst,
+}
+
+impl SortMode {
+    const ALL: [SortMode; 5] = [
+        SortMode::Created,
+        SortMode::LastActivity,
+        SortMode::Name,
+        SortMode::Size,
+        SortMode::DirtyFirst,
+    ];
+
+    /// Next mode when cycling with the sort key
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&mode| mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Created => "created",
+            SortMode::LastActivity => "last activity",
+            SortMode::Name => "name",
+            SortMode::Size => "size",
+            SortMode::DirtyFirst => "dirty first",
+        }
+    }
+}
+
+/// Grouping of the workspace list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupMode {
+    #[default]
+    None,
+    /// Part of the branch name before the first '/' (e.g. "work" for "work/20250625-fix")
+    BranchPrefix,
+}
+
+impl GroupMode {
+    pub fn next(self) -> Self {
+        match self {
+            GroupMode::None => GroupMode::BranchPrefix,
+            GroupMode::BranchPrefix => GroupMode::None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GroupMode::None => "none",
+            GroupMode::BranchPrefix => "branch prefix",
+        }
+    }
+
+    /// Group a workspace belongs to (None when grouping is off or the branch has no prefix)
+    pub fn group_of(self, workspace: &WorkspaceInfo) -> Option<&str> {
+        match self {
+            GroupMode::None => None,
+            GroupMode::BranchPrefix => workspace.branch.split_once('/').map(|(prefix, _)| prefix),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_mode_cycles_through_all_modes() {
+        let mut mode = SortMode::default();
+        let mut seen = Vec::new();
+        for _ in 0..SortMode::ALL.len() {
+            seen.push(mode);
+            mode = mode.next();
+        }
+        assert_eq!(mode, SortMode::Created);
+        assert_eq!(seen, SortMode::ALL.to_vec());
+    }
+
+    #[test]
+    fn test_group_of_branch_prefix() {
+        let workspace = |branch: &str| WorkspaceInfo {
+            name: "20250625-120000-task".to_string(),
+            path: "/ws/20250625-120000-task".to_string(),
+            branch: branch.to_string(),
+            locked: None,
+        };
+
+        let mode = GroupMode::BranchPrefix;
+        assert_eq!(mode.group_of(&workspace("work/task")), Some("work"));
+        assert_eq!(
+            mode.group_of(&workspace("feature/ui/task")),
+            Some("feature")
+        );
+        assert_eq!(mode.group_of(&workspace("task")), None);
+        assert_eq!(GroupMode::None.group_of(&workspace("work/task")), None);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::config::{ThemeName, TuiSettings};
+use ratatui::style::{Color, Modifier, Style};
+use std::str::FromStr;
+
+/// Colours of the TUI by role
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Plain text in dialogs
+    pub text: Color,
+    /// Paths, hints and other secondary text
+    pub muted: Color,
+    /// Borders, headers and key hints
+    pub accent: Color,
+    /// Highlighted row and focused fields
+    pub selected: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub success: Color,
+    /// Filter matches and in-progress operations
+    pub highlight: Color,
+    /// Background of dialogs
+    pub background: Color,
+    /// False under NO_COLOR: emphasis is shown with modifiers only
+    pub colors: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Role names accepted under `tui.colors`
+    pub const ROLES: [&'static str; 9] = [
+        "text",
+        "muted",
+        "accent",
+        "selected",
+        "warning",
+        "error",
+        "success",
+        "highlight",
+        "background",
+    ];
+
+    pub fn dark() -> Self {
+        Self {
+            text: Color::White,
+            muted: Color::Gray,
+            accent: Color::Cyan,
+            selected: Color::Yellow,
+            warning: Color::Yellow,
+            error: Color::Red,
+            success: Color::Green,
+            highlight: Color::Magenta,
+            background: Color::Black,
+            colors: true,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            text: Color::Black,
+            muted: Color::DarkGray,
+            accent: Color::Blue,
+            selected: Color::Magenta,
+            warning: Color::Indexed(130),
+            error: Color::Red,
+            success: Color::Indexed(28),
+            highlight: Color::Indexed(166),
+            background: Color::White,
+            colors: true,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            text: Color::White,
+            muted: Color::White,
+            accent: Color::LightCyan,
+            selected: Color::LightYellow,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            success: Color::LightGreen,
+            highlight: Color::LightMagenta,
+            background: Color::Black,
+            colors: true,
+        }
+    }
+
+    /// Terminal default colours only
+    pub fn no_color() -> Self {
+        Self {
+            text: Color::Reset,
+            muted: Color::Reset,
+            accent: Color::Reset,
+            selected: Color::Reset,
+            warning: Color::Reset,
+            error: Color::Reset,
+            success: Color::Reset,
+            highlight: Color::Reset,
+            background: Color::Reset,
+            colors: false,
+        }
+    }
+
+    pub fn builtin(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Self::dark(),
+            ThemeName::Light => Self::light(),
+            ThemeName::HighContrast => Self::high_contrast(),
+        }
+    }
+
+    /// Theme of the configuration file; NO_COLOR (https://no-color.org) takes precedence
+    pub fn from_config(settings: &TuiSettings) -> Result<Self, String> {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self::from_config_with_no_color(settings, no_color)
+    }
+
+    fn from_config_with_no_color(settings: &TuiSettings, no_color: bool) -> Result<Self, String> {
+        let mut theme = Self::builtin(settings.theme);
+        for (role, value) in &settings.colors {
+            let color =
+                Color::from_str(value).map_err(|_| format!("{role}: invalid colour '{value}'"))?;
+            *theme.role_mut(role).ok_or_else(|| {
+                format!(
+                    "Unknown colour role: {role} (expected one of {})",
+                    Self::ROLES.join(", ")
+                )
+            })? = color;
+        }
+        // The palette is still validated so that mistakes surface without NO_COLOR
+        Ok(if no_color { Self::no_color() } else { theme })
+    }
+
+    fn role_mut(&mut self, role: &str) -> Option<&mut Color> {
+        match role {
+            "text" => Some(&mut self.text),
+            "muted" => Some(&mut self.muted),
+            "accent" => Some(&mut self.accent),
+            "selected" => Some(&mut self.selected),
+            "warning" => Some(&mut self.warning),
+            "error" => Some(&mut self.error),
+            "success" => Some(&mut self.success),
+            "highlight" => Some(&mut self.highlight),
+            "background" => Some(&mut self.background),
+            _ => None,
+        }
+    }
+
+    /// Highlighted row and focused fields, reversed when colours are disabled
+    pub fn selected_style(&self) -> Style {
+        let style = Style::default()
+            .fg(self.selected)
+            .add_modifier(Modifier::BOLD);
+        if self.colors {
+            style
+        } else {
+            style.add_modifier(Modifier::REVERSED)
+        }
+    }
+
+    /// Matched characters of the filter, underlined when colours are disabled
+    pub fn highlight_style(&self, base: Style) -> Style {
+        let style = base.fg(self.highlight).add_modifier(Modifier::BOLD);
+        if self.colors {
+            style
+        } else {
+            style.add_modifier(Modifier::UNDERLINED)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn settings(theme: ThemeName, colors: &[(&str, &str)]) -> TuiSettings {
+        TuiSettings {
+            theme,
+            colors: colors
+                .iter()
+                .map(|(role, value)| (role.to_string(), value.to_string()))
+                .collect::<BTreeMap<_, _>>(),
+            ..TuiSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_theme_from_config() {
+        let theme =
+            Theme::from_config_with_no_color(&settings(ThemeName::Dark, &[]), false).unwrap();
+        assert_eq!(theme, Theme::dark());
+
+        let theme = Theme::from_config_with_no_color(
+            &settings(
+                ThemeName::Light,
+                &[
+                    ("accent", "#268bd2"),
+                    ("muted", "244"),
+                    ("error", "light-red"),
+                ],
+            ),
+            false,
+        )
+        .unwrap();
+        assert_eq!(theme.accent, Color::Rgb(0x26, 0x8b, 0xd2));
+        assert_eq!(theme.muted, Color::Indexed(244));
+        assert_eq!(theme.error, Color::LightRed);
+        assert_eq!(theme.background, Theme::light().background);
+
+        let error = Theme::from_config_with_no_color(
+            &settings(ThemeName::Dark, &[("border", "red")]),
+            false,
+        )
+        .unwrap_err();
+        assert!(error.contains("Unknown colour role"));
+        let error = Theme::from_config_with_no_color(
+            &settings(ThemeName::Dark, &[("accent", "sky")]),
+            false,
+        )
+        .unwrap_err();
+        assert!(error.contains("invalid colour"));
+
+        // Every documented role can be overridden
+        for role in Theme::ROLES {
+            assert!(Theme::dark().role_mut(role).is_some(), "{role}");
+        }
+    }
+
+    #[test]
+    fn test_theme_no_color() {
+        let theme = Theme::from_config_with_no_color(
+            &settings(ThemeName::HighContrast, &[("accent", "blue")]),
+            true,
+        )
+        .unwrap();
+        assert_eq!(theme, Theme::no_color());
+        assert!(theme
+            .selected_style()
+            .add_modifier
+            .contains(Modifier::REVERSED));
+        assert!(!Theme::dark()
+            .selected_style()
+            .add_modifier
+            .contains(Modifier::REVERSED));
+
+        // Invalid palettes are still reported
+        assert!(Theme::from_config_with_no_color(
+            &settings(ThemeName::Dark, &[("accent", "sky")]),
+            true
+        )
+        .is_err());
+    }
+}
@@ -1,13 +1,34 @@
+use crate::diff::{DiffLineKind, DiffTarget};
+use crate::size::{format_size, DirectorySize};
+use crate::tui::app::{
+    ClickAreas, CreateField, CreateForm, CreateProgress, DialogButton, DiffView, RenameForm,
+};
+use crate::tui::loader::LoadState;
+use crate::tui::sort::GroupMode;
+use crate::tui::theme::Theme;
 use crate::tui::App;
+use crate::workspace::{BranchTracking, WorkspaceStatus};
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
-pub fn draw(f: &mut Frame, app: &App, workspace_manager: &crate::workspace::WorkspaceManager) {
+/// Narrowest list area that still gets a preview pane
+const PREVIEW_MIN_WIDTH: u16 = 100;
+
+/// Marker of the highlighted row in lists
+const HIGHLIGHT_SYMBOL: &str = "→ ";
+
+/// Lines of the setup log shown in the preview (the end of the log)
+const PREVIEW_LOG_LINES: usize = 10;
+
+/// Draw the frame and return the regions that react to mouse clicks
+pub fn draw(f: &mut Frame, app: &App) -> ClickAreas {
+    let theme = &app.theme;
+    let mut click_areas = ClickAreas::default();
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
@@ -19,22 +40,41 @@ pub fn draw(f: &mut Frame, app: &App, workspace_manager: &crate::workspace::Work
 
     // Header
     let header = Paragraph::new("Git Worktree Manager")
-        .style(Style::default().fg(Color::Cyan))
+        .style(Style::default().fg(theme.accent))
         .block(Block::default().borders(Borders::ALL).title("gitws"));
     f.render_widget(header, chunks[0]);
 
     // Help text
     let help_text = if app.is_in_delete_confirmation() {
         Paragraph::new("Y: Confirm deletion  N: Cancel  Esc: Cancel")
-            .style(Style::default().fg(Color::Red))
+            .style(Style::default().fg(theme.error))
             .block(Block::default().borders(Borders::ALL))
     } else if app.is_in_details_view() {
         Paragraph::new("Press any key to close")
-            .style(Style::default().fg(Color::Cyan))
+            .style(Style::default().fg(theme.accent))
+            .block(Block::default().borders(Borders::ALL))
+    } else if app.is_filtering {
+        Paragraph::new(format!("/{}█", app.filter))
+            .style(Style::default().fg(theme.highlight))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Filter (Enter: Apply  Esc: Clear)"),
+            )
+    } else if let Some(message) = &app.message {
+        Paragraph::new(message.as_str())
+            .style(Style::default().fg(theme.warning))
+            .block(Block::default().borders(Borders::ALL))
+    } else if !app.pending_keys.is_empty() {
+        let pending: Vec<String> = app.pending_keys.iter().map(|key| key.to_string()).collect();
+        Paragraph::new(format!("{} …  (Esc: Cancel)", pending.join(" ")))
+            .style(Style::default().fg(theme.accent))
             .block(Block::default().borders(Borders::ALL))
     } else {
-        Paragraph::new("↑/↓: Select  Space: Multi-select  a: All  d: Delete  i: Details  q: Quit")
-            .style(Style::default().fg(Color::Gray))
+        // Inside the borders of the help box
+        let width = f.area().width.saturating_sub(4) as usize;
+        Paragraph::new(app.keymap.help_line(width))
+            .style(Style::default().fg(theme.muted))
             .block(Block::default().borders(Borders::ALL))
     };
 
@@ -49,28 +89,39 @@ pub fn draw(f: &mut Frame, app: &App, workspace_manager: &crate::workspace::Work
 
     f.render_widget(help_text, content_layout[0]);
 
+    // Preview pane on the right, hidden on narrow terminals
+    let (list_area, preview_area) =
+        if app.show_preview && content_layout[1].width >= PREVIEW_MIN_WIDTH {
+            let panes = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(content_layout[1]);
+            (panes[0], Some(panes[1]))
+        } else {
+            (content_layout[1], None)
+        };
+
     // Workspace list
     if app.workspaces.is_empty() {
         let empty_msg = Paragraph::new(
             "No worktree found.\n\nCreate a workspace with 'gitws start <task-name>'.",
         )
-        .style(Style::default().fg(Color::Yellow))
+        .style(Style::default().fg(theme.warning))
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title("Worktree List"),
         );
-        f.render_widget(empty_msg, content_layout[1]);
+        f.render_widget(empty_msg, list_area);
     } else {
-        let items: Vec<ListItem> = app
-            .workspaces
+        let visible = app.visible_indices();
+        let rows: Vec<ListItem> = visible
             .iter()
-            .enumerate()
-            .map(|(i, workspace)| {
+            .map(|&i| {
+                let workspace = &app.workspaces[i];
+                let matched = app.filter_match(workspace).unwrap_or_default();
                 let style = if i == app.selected_index {
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD)
+                    theme.selected_style()
                 } else {
                     Style::default()
                 };
@@ -82,34 +133,135 @@ pub fn draw(f: &mut Frame, app: &App, workspace_manager: &crate::workspace::Work
                     "[ ]"
                 };
 
-                let content = vec![
-                    Line::from(vec![Span::styled(
-                        format!("{checkbox} {}", workspace.branch),
-                        style,
-                    )]),
-                    Line::from(vec![Span::styled(
-                        format!("  └─ {}", workspace.path),
-                        Style::default().fg(Color::Gray),
-                    )]),
-                ];
+                let mut title = vec![Span::styled(format!("{checkbox} "), style)];
+                if workspace.is_locked() {
+                    title.push(Span::styled("🔒 ", Style::default().fg(theme.warning)));
+                }
+                title.extend(highlighted_spans(
+                    &workspace.branch,
+                    &matched.branch,
+                    style,
+                    theme,
+                ));
+                match app.summaries.get(&workspace.name) {
+                    Some(LoadState::Loaded(summary)) => {
+                        if let Some(status) = &summary.status {
+                            title.extend(status_spans(status, theme));
+                        }
+                        title.extend(tracking_spans(&summary.tracking, theme));
+                    }
+                    Some(LoadState::Loading) => title.push(Span::styled(
+                        format!("  {}", app.spinner()),
+                        Style::default().fg(theme.muted),
+                    )),
+                    Some(LoadState::Failed(_)) => {
+                        title.push(Span::styled("  ?", Style::default().fg(theme.error)))
+                    }
+                    None => {}
+                }
+
+                let mut path_line = vec![Span::styled("  └─ ", Style::default().fg(theme.muted))];
+                path_line.extend(highlighted_spans(
+                    &workspace.path,
+                    &matched.path,
+                    Style::default().fg(theme.muted),
+                    theme,
+                ));
+                let content = vec![Line::from(title), Line::from(path_line)];
 
                 ListItem::new(content).style(style)
             })
             .collect();
 
-        let list = List::new(items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(format!("Worktree List ({} items)", app.workspaces.len())),
+        // Insert a header row before each group
+        let mut items = Vec::with_capacity(rows.len());
+        let mut item_workspaces = Vec::with_capacity(rows.len()); // None for group headers
+        let mut selected_row = None;
+        let mut current_group = None;
+        for (row, &i) in rows.into_iter().zip(&visible) {
+            let workspace = &app.workspaces[i];
+            if app.group_mode != GroupMode::None
+                && (items.is_empty() || app.group_of(workspace) != current_group)
+            {
+                current_group = app.group_of(workspace);
+                let count = visible
+                    .iter()
+                    .filter(|&&j| app.group_of(&app.workspaces[j]) == current_group)
+                    .count();
+                items.push(ListItem::new(Line::from(Span::styled(
+                    format!("▾ {} ({count})", current_group.unwrap_or("(no prefix)")),
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                ))));
+                item_workspaces.push(None);
+            }
+            if i == app.selected_index {
+                selected_row = Some(items.len());
+            }
+            items.push(row);
+            item_workspaces.push(Some(i));
+        }
+        let item_heights: Vec<u16> = items.iter().map(|item| item.height() as u16).collect();
+
+        let mut list_title = if app.filter.is_empty() {
+            format!("Worktree List ({} items", app.workspaces.len())
+        } else {
+            format!(
+                "Worktree List ({}/{} items, filter: {}",
+                visible.len(),
+                app.workspaces.len(),
+                app.filter
             )
+        };
+        list_title.push_str(&format!(", sort: {}", app.sort_mode.label()));
+        if app.group_mode != GroupMode::None {
+            list_title.push_str(&format!(", group: {}", app.group_mode.label()));
+        }
+        list_title.push(')');
+
+        let block = Block::default().borders(Borders::ALL).title(list_title);
+        let inner = block.inner(list_area);
+        let list = List::new(items)
+            .block(block)
             .highlight_style(Style::default().add_modifier(Modifier::BOLD))
-            .highlight_symbol("→ ");
+            .highlight_symbol(HIGHLIGHT_SYMBOL);
 
         let mut list_state = ListState::default();
-        list_state.select(Some(app.selected_index));
+        list_state.select(selected_row);
+
+        f.render_stateful_widget(list, list_area, &mut list_state);
+
+        // Rows scrolled into view; the checkbox follows the space reserved for the highlight symbol
+        let checkbox_x = if selected_row.is_some() {
+            inner.x + HIGHLIGHT_SYMBOL.chars().count() as u16
+        } else {
+            inner.x
+        };
+        let mut y = inner.y;
+        for (height, workspace) in item_heights
+            .into_iter()
+            .zip(item_workspaces)
+            .skip(list_state.offset())
+        {
+            if y >= inner.bottom() {
+                break;
+            }
+            let height = height.min(inner.bottom() - y);
+            if let Some(i) = workspace {
+                click_areas
+                    .rows
+                    .push((Rect::new(inner.x, y, inner.width, height), i));
+                click_areas
+                    .checkboxes
+                    .push((Rect::new(checkbox_x, y, 3, 1), i));
+            }
+            y += height;
+        }
+    }
 
-        f.render_stateful_widget(list, content_layout[1], &mut list_state);
+    if let Some(area) = preview_area {
+        draw_preview(f, app, area);
     }
 
     // Selection status
@@ -123,7 +275,7 @@ pub fn draw(f: &mut Frame, app: &App, workspace_manager: &crate::workspace::Work
         };
 
         let status = Paragraph::new(status_text)
-            .style(Style::default().fg(Color::Cyan))
+            .style(Style::default().fg(theme.accent))
             .block(Block::default().borders(Borders::ALL));
         f.render_widget(status, content_layout[2]);
     }
@@ -131,31 +283,620 @@ pub fn draw(f: &mut Frame, app: &App, workspace_manager: &crate::workspace::Work
     // Details dialog
     if app.is_in_details_view() {
         if let Some(workspace) = app.get_selected_workspace() {
-            draw_workspace_details_dialog(f, workspace, workspace_manager);
+            draw_workspace_details_dialog(f, app, workspace);
         }
     }
 
+    // Diff viewer
+    if let Some(view) = &app.diff_view {
+        draw_diff_view(f, app, view, &mut click_areas);
+    }
+
+    // Workspace creation
+    if let Some(form) = &app.create_form {
+        draw_create_form(f, form, theme);
+    }
+    if app.show_help {
+        draw_help(f, app);
+    }
+    if let Some(form) = &app.rename_form {
+        draw_rename_form(f, form, theme);
+    }
+    if let Some(progress) = &app.create_progress {
+        draw_create_progress(f, app, progress);
+    }
+
     // Delete confirmation dialog
     if app.is_in_delete_confirmation() {
         let selected_count = app.get_selected_count();
         if selected_count > 1 {
             // Bulk delete confirmation
             let selected_workspaces = app.get_selected_workspaces();
-            draw_bulk_delete_confirmation_dialog(f, &selected_workspaces);
+            draw_bulk_delete_confirmation_dialog(f, &selected_workspaces, theme, &mut click_areas);
         } else if selected_count == 1 {
             // Single selected workspace delete
             let selected_workspaces = app.get_selected_workspaces();
             if let Some(workspace) = selected_workspaces.first() {
-                draw_delete_confirmation_dialog(f, &workspace.name, &workspace.path);
+                draw_delete_confirmation_dialog(
+                    f,
+                    &workspace.name,
+                    &workspace.path,
+                    theme,
+                    &mut click_areas,
+                );
             }
         } else if let Some(workspace) = app.get_selected_workspace() {
             // Current workspace delete (no multi-selection)
-            draw_delete_confirmation_dialog(f, &workspace.name, &workspace.path);
+            draw_delete_confirmation_dialog(
+                f,
+                &workspace.name,
+                &workspace.path,
+                theme,
+                &mut click_areas,
+            );
+        }
+    }
+
+    click_areas
+}
+
+/// Split text into spans, highlighting the characters at `indices`
+fn highlighted_spans(
+    text: &str,
+    indices: &[usize],
+    style: Style,
+    theme: &Theme,
+) -> Vec<Span<'static>> {
+    if indices.is_empty() {
+        return vec![Span::styled(text.to_string(), style)];
+    }
+
+    let highlight = theme.highlight_style(style);
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut current_matched = false;
+    for (i, c) in text.chars().enumerate() {
+        let matched = indices.contains(&i);
+        if matched != current_matched && !current.is_empty() {
+            let s = if current_matched { highlight } else { style };
+            spans.push(Span::styled(std::mem::take(&mut current), s));
+        }
+        current_matched = matched;
+        current.push(c);
+    }
+    if !current.is_empty() {
+        spans.push(Span::styled(
+            current,
+            if current_matched { highlight } else { style },
+        ));
+    }
+    spans
+}
+
+/// Compact working tree state shown after the branch name in list rows
+/// (+staged ~unstaged ?untracked !conflicted)
+fn status_spans(status: &WorkspaceStatus, theme: &Theme) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+
+    if let Some(operation) = status.operation {
+        spans.push(Span::styled(
+            format!("  [{operation}]"),
+            Style::default()
+                .fg(theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    for (count, symbol, color) in [
+        (status.staged, "+", theme.success),
+        (status.unstaged, "~", theme.warning),
+        (status.untracked, "?", theme.muted),
+        (status.conflicted, "!", theme.error),
+    ] {
+        if count > 0 {
+            spans.push(Span::styled(
+                format!(" {symbol}{count}"),
+                Style::default().fg(color),
+            ));
+        }
+    }
+
+    spans
+}
+
+fn status_color(status: Option<&WorkspaceStatus>, theme: &Theme) -> Color {
+    match status {
+        Some(status) if status.operation.is_some() || status.conflicted > 0 => theme.error,
+        Some(status) if !status.is_clean() => theme.warning,
+        _ => theme.success,
+    }
+}
+
+/// Largest subdirectories for the details dialog
+fn largest_dirs_text(usage: &DirectorySize) -> String {
+    if usage.largest_dirs.is_empty() {
+        return "--".to_string();
+    }
+    usage
+        .largest_dirs
+        .iter()
+        .map(|(name, bytes)| format!("{name} {}", format_size(*bytes)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Compact ahead/behind summary shown after the branch name in list rows
+fn tracking_spans(tracking: &BranchTracking, theme: &Theme) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+
+    if let (Some(base), Some(counts)) = (&tracking.base, tracking.base_counts) {
+        spans.push(Span::styled(
+            format!("  {base} ↑{} ↓{}", counts.ahead, counts.behind),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    if tracking.merged {
+        spans.push(Span::styled("  merged", Style::default().fg(theme.success)));
+    }
+    match (&tracking.upstream, tracking.upstream_counts) {
+        (Some(upstream), Some(counts)) => spans.push(Span::styled(
+            format!("  {upstream} ↑{} ↓{}", counts.ahead, counts.behind),
+            Style::default().fg(theme.accent),
+        )),
+        (Some(upstream), None) => spans.push(Span::styled(
+            format!("  {upstream} (gone)"),
+            Style::default().fg(theme.error),
+        )),
+        (None, _) => spans.push(Span::styled(
+            "  not pushed",
+            Style::default().fg(theme.warning),
+        )),
+    }
+
+    spans
+}
+
+/// Tracking lines for the details dialog
+fn tracking_text(tracking: &BranchTracking) -> String {
+    let base = match (&tracking.base, tracking.base_counts) {
+        (Some(base), Some(counts)) => format!(
+            "{base} (ahead {}, behind {}{})",
+            counts.ahead,
+            counts.behind,
+            if tracking.merged { ", merged" } else { "" }
+        ),
+        (Some(base), None) => format!("{base} (not found)"),
+        (None, _) => "Unknown".to_string(),
+    };
+    let upstream = match (&tracking.upstream, tracking.upstream_counts) {
+        (Some(upstream), Some(counts)) => format!(
+            "{upstream} (ahead {}, behind {}{})",
+            counts.ahead,
+            counts.behind,
+            if tracking.pushed { ", pushed" } else { "" }
+        ),
+        (Some(upstream), None) => format!("{upstream} (gone)"),
+        (None, _) => "None (not pushed)".to_string(),
+    };
+    format!("Base: {base}\nUpstream: {upstream}")
+}
+
+/// Status, tracking, commits, changed files and setup log of the highlighted workspace
+fn draw_preview(f: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let block = Block::default().borders(Borders::ALL);
+    let Some(workspace) = app.get_selected_workspace() else {
+        let empty = Paragraph::new("No workspace selected")
+            .style(Style::default().fg(theme.muted))
+            .block(block.title("Preview"));
+        f.render_widget(empty, area);
+        return;
+    };
+    let block = block.title(format!("Preview: {}", workspace.task_name()));
+
+    let details = match app.details.get(&workspace.name) {
+        Some(LoadState::Loaded(details)) => details,
+        Some(LoadState::Failed(e)) => {
+            let error = Paragraph::new(format!("Error: {e}"))
+                .style(Style::default().fg(theme.error))
+                .block(block);
+            f.render_widget(error, area);
+            return;
+        }
+        Some(LoadState::Loading) | None => {
+            let loading = Paragraph::new(format!("{} Loading...", app.spinner()))
+                .style(Style::default().fg(theme.muted))
+                .block(block);
+            f.render_widget(loading, area);
+            return;
         }
+    };
+
+    let heading = |text: &'static str| {
+        Line::from(Span::styled(
+            text,
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+    };
+
+    let mut lines = vec![
+        Line::from(format!("Branch: {}", workspace.branch)),
+        Line::from(Span::styled(
+            format!("Status: {} ({})", details.status, details.files_info),
+            Style::default().fg(status_color(details.workspace_status.as_ref(), theme)),
+        )),
+    ];
+    lines.extend(tracking_text(&details.tracking).lines().map(|line| {
+        Line::from(Span::styled(
+            line.to_string(),
+            Style::default().fg(theme.accent),
+        ))
+    }));
+
+    lines.push(Line::default());
+    lines.push(heading("Recent Commits:"));
+    if details.recent_commits.is_empty() {
+        lines.push(Line::from("None"));
+    }
+    lines.extend(details.recent_commits.iter().map(|commit| {
+        Line::from(Span::styled(
+            commit.clone(),
+            Style::default().fg(theme.warning),
+        ))
+    }));
+
+    lines.push(Line::default());
+    lines.push(heading("Changed Files:"));
+    if details.changed_files.is_empty() {
+        lines.push(Line::from("None"));
+    }
+    lines.extend(details.changed_files.iter().map(|file| {
+        let color = match &file[..2] {
+            "??" | "UU" => theme.error,
+            code if !code.starts_with(' ') => theme.success,
+            _ => theme.warning,
+        };
+        Line::from(Span::styled(file.clone(), Style::default().fg(color)))
+    }));
+
+    lines.push(Line::default());
+    lines.push(heading("Setup Log:"));
+    match &details.setup_log {
+        Some(log) => {
+            let log_lines: Vec<&str> = log.lines().collect();
+            let start = log_lines.len().saturating_sub(PREVIEW_LOG_LINES);
+            lines.extend(log_lines[start..].iter().map(|line| {
+                Line::from(Span::styled(
+                    line.to_string(),
+                    Style::default().fg(theme.muted),
+                ))
+            }));
+        }
+        None => lines.push(Line::from("None")),
     }
+
+    let preview = Paragraph::new(lines)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(preview, area);
 }
 
-fn draw_delete_confirmation_dialog(f: &mut Frame, workspace_name: &str, workspace_path: &str) {
+/// Rectangle of the given size centered in the frame
+fn centered_rect(f: &Frame, width: u16, height: u16) -> Rect {
+    let area = f.area();
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: (area.width - width) / 2,
+        y: (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn draw_create_form(f: &mut Frame, form: &CreateForm, theme: &Theme) {
+    let popup_area = centered_rect(f, 64, 9);
+    f.render_widget(Clear, popup_area);
+
+    let field = |label: &str, value: &str, placeholder: &str, focused: bool| {
+        let label_style = if focused {
+            theme.selected_style()
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![Span::styled(format!("{label:<11}"), label_style)];
+        if value.is_empty() && !focused {
+            spans.push(Span::styled(
+                placeholder.to_string(),
+                Style::default().fg(theme.muted),
+            ));
+        } else {
+            spans.push(Span::raw(value.to_string()));
+        }
+        if focused {
+            spans.push(Span::raw("█"));
+        }
+        Line::from(spans)
+    };
+
+    let mut lines = vec![
+        field(
+            "Task name:",
+            &form.task_name,
+            "",
+            form.focus == CreateField::TaskName,
+        ),
+        field(
+            "Base ref:",
+            &form.base_ref,
+            "(current HEAD)",
+            form.focus == CreateField::BaseRef,
+        ),
+        Line::default(),
+    ];
+    if let Some(error) = &form.error {
+        lines.push(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(theme.error),
+        )));
+    }
+
+    let dialog = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title("New Workspace")
+            .title_bottom("Tab: Next field  Enter: Create  Esc: Cancel"),
+    );
+    f.render_widget(dialog, popup_area);
+}
+
+/// Key bindings of the active keymap
+fn draw_help(f: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let entries = app.keymap.help_entries();
+    let key_width = entries
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<Line> = entries
+        .into_iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{keys:<key_width$}  "),
+                    Style::default()
+                        .fg(theme.selected)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(description),
+            ])
+        })
+        .collect();
+
+    let width = (key_width + 50) as u16;
+    let popup_area = centered_rect(f, width, lines.len() as u16 + 2);
+    f.render_widget(Clear, popup_area);
+    let help = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title("Key Bindings")
+            .title_bottom("Press any key to close"),
+    );
+    f.render_widget(help, popup_area);
+}
+
+fn draw_rename_form(f: &mut Frame, form: &RenameForm, theme: &Theme) {
+    let popup_area = centered_rect(f, 64, 8);
+    f.render_widget(Clear, popup_area);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("Workspace: ", Style::default().fg(theme.muted)),
+            Span::raw(form.workspace.name.clone()),
+        ]),
+        Line::from(vec![
+            Span::styled("New name:  ", theme.selected_style()),
+            Span::raw(form.new_name.clone()),
+            Span::raw("█"),
+        ]),
+        Line::default(),
+    ];
+    if let Some(error) = &form.error {
+        lines.push(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(theme.error),
+        )));
+    }
+
+    let dialog = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title("Rename Workspace")
+            .title_bottom("Enter: Rename  Esc: Cancel"),
+    );
+    f.render_widget(dialog, popup_area);
+}
+
+/// Copy and setup output of a workspace being created
+fn draw_create_progress(f: &mut Frame, app: &App, progress: &CreateProgress) {
+    let theme = &app.theme;
+    let area = f.area();
+    let popup_area = centered_rect(f, area.width * 4 / 5, area.height * 7 / 10);
+    f.render_widget(Clear, popup_area);
+
+    let (status, style) = match &progress.result {
+        None => (
+            format!("{} Creating...", app.spinner()),
+            Style::default().fg(theme.muted),
+        ),
+        Some(Ok(name)) => (
+            format!("✅ Created {name}  (Enter: Close)"),
+            Style::default().fg(theme.success),
+        ),
+        Some(Err(e)) => (
+            format!("❌ {e}  (Enter: Close)"),
+            Style::default().fg(theme.error),
+        ),
+    };
+
+    // Keep the latest output visible unless scrolled back
+    let visible_lines = popup_area.height.saturating_sub(4) as usize;
+    let end = progress.lines.len().saturating_sub(progress.scroll_back);
+    let start = end.saturating_sub(visible_lines);
+    let mut lines: Vec<Line> = progress.lines[start..end]
+        .iter()
+        .map(|line| Line::from(line.clone()))
+        .collect();
+    lines.push(Line::default());
+    lines.push(Line::from(Span::styled(status, style)));
+
+    let panel = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.accent))
+            .title(format!("Creating {}", progress.task_name)),
+    );
+    f.render_widget(panel, popup_area);
+}
+
+/// Changed files and the scrollable unified diff of the selected one
+fn draw_diff_view(f: &mut Frame, app: &App, view: &DiffView, click_areas: &mut ClickAreas) {
+    let theme = &app.theme;
+    let area = f.area();
+    let popup_area = Rect {
+        x: 2.min(area.width),
+        y: 1.min(area.height),
+        width: area.width.saturating_sub(4),
+        height: area.height.saturating_sub(2),
+    };
+    f.render_widget(Clear, popup_area);
+
+    let target = match view.target {
+        DiffTarget::Base => "base branch",
+        DiffTarget::Head => "HEAD",
+    };
+    let title = match &view.diff {
+        LoadState::Loaded(diff) => format!(
+            "Diff: {} vs {}",
+            view.workspace.task_name(),
+            diff.compared_with
+        ),
+        _ => format!("Diff: {} vs {target}", view.workspace.task_name()),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(title)
+        .title_bottom("Tab: Base/HEAD  ←/→: File  ↑/↓ PgUp/PgDn: Scroll  q: Close");
+    let inner = block.inner(popup_area);
+    f.render_widget(block, popup_area);
+
+    let diff = match &view.diff {
+        LoadState::Loaded(diff) => diff,
+        LoadState::Loading => {
+            let loading = Paragraph::new(format!("{} Loading...", app.spinner()))
+                .style(Style::default().fg(theme.muted));
+            f.render_widget(loading, inner);
+            return;
+        }
+        LoadState::Failed(e) => {
+            let error =
+                Paragraph::new(format!("Error: {e}")).style(Style::default().fg(theme.error));
+            f.render_widget(error, inner);
+            return;
+        }
+    };
+    if diff.files.is_empty() {
+        let empty = Paragraph::new(format!("No changes against {target}"))
+            .style(Style::default().fg(theme.success));
+        f.render_widget(empty, inner);
+        return;
+    }
+
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(inner);
+
+    // Changed files with +/- counts
+    let files: Vec<ListItem> = diff
+        .files
+        .iter()
+        .map(|file| {
+            let color = match file.status {
+                'A' => theme.success,
+                'D' => theme.error,
+                _ => theme.warning,
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", file.status), Style::default().fg(color)),
+                Span::raw(file.path.clone()),
+                Span::styled(
+                    format!(" +{}", file.additions),
+                    Style::default().fg(theme.success),
+                ),
+                Span::styled(
+                    format!(" -{}", file.deletions),
+                    Style::default().fg(theme.error),
+                ),
+            ]))
+        })
+        .collect();
+    let file_block = Block::default()
+        .borders(Borders::RIGHT)
+        .title(format!("{} files", diff.files.len()));
+    let file_area = file_block.inner(panes[0]);
+    let file_list = List::new(files)
+        .block(file_block)
+        .highlight_style(theme.selected_style())
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
+    let mut file_state = ListState::default();
+    file_state.select(Some(view.selected_file));
+    f.render_stateful_widget(file_list, panes[0], &mut file_state);
+    for (row, index) in (file_state.offset()..diff.files.len())
+        .take(file_area.height as usize)
+        .enumerate()
+    {
+        let y = file_area.y + row as u16;
+        click_areas
+            .diff_files
+            .push((Rect::new(file_area.x, y, file_area.width, 1), index));
+    }
+
+    // Unified diff of the selected file
+    let lines: Vec<Line> = diff
+        .files
+        .get(view.selected_file)
+        .map(|file| {
+            file.lines
+                .iter()
+                .map(|line| {
+                    let style = match line.kind {
+                        DiffLineKind::FileHeader => Style::default().add_modifier(Modifier::BOLD),
+                        DiffLineKind::HunkHeader => Style::default().fg(theme.accent),
+                        DiffLineKind::Addition => Style::default().fg(theme.success),
+                        DiffLineKind::Deletion => Style::default().fg(theme.error),
+                        DiffLineKind::Context => Style::default(),
+                    };
+                    Line::from(Span::styled(line.text.clone(), style))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let patch = Paragraph::new(lines).scroll((view.scroll, 0));
+    f.render_widget(patch, panes[1].inner(ratatui::layout::Margin::new(1, 0)));
+}
+
+fn draw_delete_confirmation_dialog(
+    f: &mut Frame,
+    workspace_name: &str,
+    workspace_path: &str,
+    theme: &Theme,
+    click_areas: &mut ClickAreas,
+) {
     // Display modal dialog in the center of the screen
     let area = f.area();
     let popup_width = 60.min(area.width);
@@ -171,9 +912,9 @@ fn draw_delete_confirmation_dialog(f: &mut Frame, workspace_name: &str, workspac
     // Clear background
     f.render_widget(
         Block::default()
-            .style(Style::default().bg(Color::Black))
+            .style(Style::default().bg(theme.background))
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Red)),
+            .border_style(Style::default().fg(theme.error)),
         popup_area,
     );
 
@@ -190,38 +931,36 @@ fn draw_delete_confirmation_dialog(f: &mut Frame, workspace_name: &str, workspac
         .split(popup_area);
 
     // Title
-    let title = Paragraph::new("Delete workspace?")
-        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+    let title = Paragraph::new("Delete workspace?").style(
+        Style::default()
+            .fg(theme.error)
+            .add_modifier(Modifier::BOLD),
+    );
     f.render_widget(title, dialog_layout[0]);
 
     // Workspace information
     let workspace_info = Paragraph::new(format!("Name: {workspace_name}\nPath: {workspace_path}"))
-        .style(Style::default().fg(Color::White));
+        .style(Style::default().fg(theme.text));
     f.render_widget(workspace_info, dialog_layout[1]);
 
     // Confirmation message
     let warning = Paragraph::new("This operation cannot be undone.")
-        .style(Style::default().fg(Color::Yellow));
+        .style(Style::default().fg(theme.warning));
     f.render_widget(warning, dialog_layout[2]);
 
-    // Operation guide
-    let guide = Paragraph::new("[Y]es  [N]o").style(
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD),
-    );
-    f.render_widget(guide, dialog_layout[3]);
+    draw_dialog_buttons(f, dialog_layout[3], theme, click_areas);
 }
 
 fn draw_workspace_details_dialog(
     f: &mut Frame,
+    app: &App,
     workspace: &crate::workspace::WorkspaceInfo,
-    workspace_manager: &crate::workspace::WorkspaceManager,
 ) {
+    let theme = &app.theme;
     // Display modal dialog in the center of the screen
     let area = f.area();
     let popup_width = 80.min(area.width);
-    let popup_height = 16.min(area.height);
+    let popup_height = 19.min(area.height);
 
     let popup_area = ratatui::layout::Rect {
         x: (area.width.saturating_sub(popup_width)) / 2,
@@ -233,9 +972,9 @@ fn draw_workspace_details_dialog(
     // Background block
     f.render_widget(
         Block::default()
-            .style(Style::default().bg(Color::Black))
+            .style(Style::default().bg(theme.background))
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Cyan))
+            .border_style(Style::default().fg(theme.accent))
             .title("Workspace Details"),
         popup_area,
     );
@@ -247,39 +986,72 @@ fn draw_workspace_details_dialog(
         .constraints([
             Constraint::Length(2), // Basic information
             Constraint::Length(2), // Date/time information
-            Constraint::Length(2), // Status information
+            Constraint::Length(3), // Status information
+            Constraint::Length(2), // Tracking information
             Constraint::Length(4), // Recent commits
             Constraint::Length(1), // Operation guide
         ])
         .split(popup_area);
 
     // Basic information
+    let lock = match workspace.locked.as_deref() {
+        Some("") => "  🔒 Locked".to_string(),
+        Some(reason) => format!("  🔒 Locked: {reason}"),
+        None => String::new(),
+    };
     let basic_info = Paragraph::new(format!(
-        "Branch: {}\nPath: {}",
+        "Branch: {}{lock}\nPath: {}",
         workspace.branch, workspace.path
     ))
-    .style(Style::default().fg(Color::White));
+    .style(Style::default().fg(theme.text));
     f.render_widget(basic_info, dialog_layout[0]);
 
-    // Get detailed information
-    match workspace_manager.get_workspace_details(workspace) {
-        Ok(details) => {
+    // Detailed information is loaded in the background
+    match app.details.get(&workspace.name) {
+        Some(LoadState::Loaded(details)) => {
             // Date/time information
             let time_info = Paragraph::new(format!(
                 "Created: {}\nLast Modified: {}",
                 details.created, details.last_modified
             ))
-            .style(Style::default().fg(Color::Gray));
+            .style(Style::default().fg(theme.muted));
             f.render_widget(time_info, dialog_layout[1]);
 
             // Status information
+            // Size arrives separately once the directory walk finishes
+            let (size, largest) = match app.sizes.get(&workspace.name) {
+                Some(LoadState::Loaded(usage)) => (
+                    format!(
+                        "{} (disk: {})",
+                        format_size(usage.apparent_bytes),
+                        format_size(usage.disk_bytes)
+                    ),
+                    largest_dirs_text(usage),
+                ),
+                Some(LoadState::Loading) => (
+                    format!("{} Calculating...", app.spinner()),
+                    app.spinner().to_string(),
+                ),
+                Some(LoadState::Failed(_)) | None => (
+                    details.size.clone(),
+                    details
+                        .disk_usage
+                        .as_ref()
+                        .map_or_else(|| "--".to_string(), largest_dirs_text),
+                ),
+            };
             let status_info = Paragraph::new(format!(
-                "Status: {}\nFiles: {}  Size: {}",
-                details.status, details.files_info, details.size
+                "Status: {}  Size: {}\nFiles: {}\nLargest: {}",
+                details.status, size, details.files_info, largest
             ))
-            .style(Style::default().fg(Color::Green));
+            .style(Style::default().fg(status_color(details.workspace_status.as_ref(), theme)));
             f.render_widget(status_info, dialog_layout[2]);
 
+            // Ahead/behind base branch and upstream
+            let tracking_info = Paragraph::new(tracking_text(&details.tracking))
+                .style(Style::default().fg(theme.accent));
+            f.render_widget(tracking_info, dialog_layout[3]);
+
             // Recent commit history
             let commits_text = if details.recent_commits.is_empty() {
                 "Recent Commits:\nNone".to_string()
@@ -287,37 +1059,48 @@ fn draw_workspace_details_dialog(
                 format!("Recent Commits:\n{}", details.recent_commits.join("\n"))
             };
             let commit_info =
-                Paragraph::new(commits_text).style(Style::default().fg(Color::Yellow));
-            f.render_widget(commit_info, dialog_layout[3]);
+                Paragraph::new(commits_text).style(Style::default().fg(theme.warning));
+            f.render_widget(commit_info, dialog_layout[4]);
+        }
+        Some(LoadState::Loading) | None => {
+            let loading = Paragraph::new(format!("{} Loading...", app.spinner()))
+                .style(Style::default().fg(theme.muted));
+            f.render_widget(loading, dialog_layout[1]);
         }
-        Err(_) => {
+        Some(LoadState::Failed(_)) => {
             // Display alternative text if error occurs
             let time_info = Paragraph::new("Created: Error\nLast Modified: Error")
-                .style(Style::default().fg(Color::Red));
+                .style(Style::default().fg(theme.error));
             f.render_widget(time_info, dialog_layout[1]);
 
-            let status_info = Paragraph::new("Status: Error\nFiles: --  Size: --")
-                .style(Style::default().fg(Color::Red));
+            let status_info = Paragraph::new("Status: Error  Size: --\nFiles: --\nLargest: --")
+                .style(Style::default().fg(theme.error));
             f.render_widget(status_info, dialog_layout[2]);
 
+            let tracking_info = Paragraph::new("Base: Error\nUpstream: Error")
+                .style(Style::default().fg(theme.error));
+            f.render_widget(tracking_info, dialog_layout[3]);
+
             let commit_info =
-                Paragraph::new("Recent Commits:\nError").style(Style::default().fg(Color::Red));
-            f.render_widget(commit_info, dialog_layout[3]);
+                Paragraph::new("Recent Commits:\nError").style(Style::default().fg(theme.error));
+            f.render_widget(commit_info, dialog_layout[4]);
         }
     }
 
     // Operation guide
     let guide = Paragraph::new("Press any key to close").style(
         Style::default()
-            .fg(Color::Cyan)
+            .fg(theme.accent)
             .add_modifier(Modifier::BOLD),
     );
-    f.render_widget(guide, dialog_layout[4]);
+    f.render_widget(guide, dialog_layout[5]);
 }
 
 fn draw_bulk_delete_confirmation_dialog(
     f: &mut Frame,
     workspaces: &[&crate::workspace::WorkspaceInfo],
+    theme: &Theme,
+    click_areas: &mut ClickAreas,
 ) {
     // Display modal dialog in the center of the screen
     let area = f.area();
@@ -334,9 +1117,9 @@ fn draw_bulk_delete_confirmation_dialog(
     // Clear background
     f.render_widget(
         Block::default()
-            .style(Style::default().bg(Color::Black))
+            .style(Style::default().bg(theme.background))
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Red)),
+            .border_style(Style::default().fg(theme.error)),
         popup_area,
     );
 
@@ -355,8 +1138,11 @@ fn draw_bulk_delete_confirmation_dialog(
         .split(popup_area);
 
     // Title
-    let title = Paragraph::new("Delete Multiple Workspaces")
-        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+    let title = Paragraph::new("Delete Multiple Workspaces").style(
+        Style::default()
+            .fg(theme.error)
+            .add_modifier(Modifier::BOLD),
+    );
     f.render_widget(title, dialog_layout[0]);
 
     // Question
@@ -364,7 +1150,7 @@ fn draw_bulk_delete_confirmation_dialog(
         "Are you sure you want to delete these {} workspaces?",
         workspaces.len()
     ))
-    .style(Style::default().fg(Color::White));
+    .style(Style::default().fg(theme.text));
     f.render_widget(question, dialog_layout[1]);
 
     // Workspace list
@@ -374,7 +1160,7 @@ fn draw_bulk_delete_confirmation_dialog(
         .map(|workspace| {
             Line::from(vec![Span::styled(
                 format!("• {}", workspace.name),
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme.warning),
             )])
         })
         .collect();
@@ -383,23 +1169,43 @@ fn draw_bulk_delete_confirmation_dialog(
     if workspaces.len() > 5 {
         all_lines.push(Line::from(vec![Span::styled(
             format!("... and {} more", workspaces.len() - 5),
-            Style::default().fg(Color::Gray),
+            Style::default().fg(theme.muted),
         )]));
     }
 
-    let workspace_list = Paragraph::new(all_lines).style(Style::default().fg(Color::White));
+    let workspace_list = Paragraph::new(all_lines).style(Style::default().fg(theme.text));
     f.render_widget(workspace_list, dialog_layout[2]);
 
     // Warning
     let warning =
-        Paragraph::new("This action cannot be undone.").style(Style::default().fg(Color::Yellow));
+        Paragraph::new("This action cannot be undone.").style(Style::default().fg(theme.warning));
     f.render_widget(warning, dialog_layout[3]);
 
-    // Operation guide
-    let guide = Paragraph::new("[Y]es  [N]o").style(
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD),
-    );
-    f.render_widget(guide, dialog_layout[4]);
+    draw_dialog_buttons(f, dialog_layout[4], theme, click_areas);
+}
+
+/// Yes/No buttons of a confirmation dialog, which can also be clicked
+fn draw_dialog_buttons(f: &mut Frame, area: Rect, theme: &Theme, click_areas: &mut ClickAreas) {
+    const GAP: u16 = 2;
+    let style = Style::default()
+        .fg(theme.accent)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut x = area.x;
+    for (label, button) in [
+        ("[Y]es", DialogButton::Confirm),
+        ("[N]o", DialogButton::Cancel),
+    ] {
+        if !spans.is_empty() {
+            spans.push(Span::raw(" ".repeat(GAP as usize)));
+            x += GAP;
+        }
+        let width = label.chars().count() as u16;
+        spans.push(Span::styled(label, style));
+        click_areas
+            .buttons
+            .push((Rect::new(x, area.y, width, 1).intersection(area), button));
+        x += width;
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
 }
@@ -1,10 +1,22 @@
 use chrono::{DateTime, Local};
+use std::io::{self, Write};
 
 pub fn generate_timestamp() -> String {
     let now: DateTime<Local> = Local::now();
     now.format("%Y%m%d-%H%M%S").to_string()
 }
 
+/// Ask a yes/no question on the terminal; anything but "y" or "yes" (including EOF) is no
+pub fn confirm(prompt: &str) -> io::Result<bool> {
+    print!("{prompt} (y/N): ");
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    Ok(input == "y" || input == "yes")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,40 +1,252 @@
+use crate::config::{WorkspaceConfig, WorkspaceSettings};
+use crate::diff::{workspace_diff, DiffTarget, WorkspaceDiff};
 use crate::error::{GitwsError, GitwsResult};
-use git2::{Repository, WorktreeAddOptions};
+use crate::size::{format_size, DirectorySize, SizeCache, SizeState};
+use crate::state::{load_state, save_state, GitwsState, TuiState, WorkspaceMetadata};
+use git2::{
+    Branch, BranchType, Oid, Repository, RepositoryState, Status, StatusOptions, Worktree,
+    WorktreeAddOptions, WorktreeLockStatus,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tracing::{debug, error, warn};
 
+/// Maximum number of changed files listed for a workspace
+const MAX_CHANGED_FILES: usize = 100;
+
 pub struct WorkspaceManager {
     repo: Repository,
+    is_bare: bool,
+    size_cache: SizeCache,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct WorkspaceInfo {
     pub name: String,
     pub path: String,
     pub branch: String,
+    /// Lock reason when the worktree is locked (empty when locked without a reason)
+    pub locked: Option<String>,
+}
+
+impl WorkspaceInfo {
+    pub fn is_locked(&self) -> bool {
+        self.locked.is_some()
+    }
+
+    /// Task name given at creation time (workspace name without the timestamp prefix)
+    pub fn task_name(&self) -> &str {
+        // Workspace names are "YYYYMMDD-HHMMSS-<task>"
+        let bytes = self.name.as_bytes();
+        let has_timestamp = bytes.len() > 16
+            && bytes[..8].iter().all(u8::is_ascii_digit)
+            && bytes[8] == b'-'
+            && bytes[9..15].iter().all(u8::is_ascii_digit)
+            && bytes[15] == b'-';
+        if has_timestamp {
+            &self.name[16..]
+        } else {
+            &self.name
+        }
+    }
+
+    /// Creation time encoded in the workspace name ("YYYYMMDD-HHMMSS-<task>")
+    pub fn created_at(&self) -> Option<chrono::NaiveDateTime> {
+        let timestamp = self.name.get(..15)?;
+        chrono::NaiveDateTime::parse_from_str(timestamp, "%Y%m%d-%H%M%S").ok()
+    }
+
+    /// Environment variables describing the workspace, set for commands run inside it
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("GITWS_WORKSPACE", self.name.clone()),
+            ("GITWS_WORKSPACE_PATH", self.path.clone()),
+            ("GITWS_BRANCH", self.branch.clone()),
+            ("GITWS_TASK", self.task_name().to_string()),
+        ]
+    }
+}
+
+/// Entry of `gitws list --json`
+#[derive(Debug, Serialize)]
+pub struct WorkspaceListEntry<'a> {
+    #[serde(flatten)]
+    pub workspace: &'a WorkspaceInfo,
+    pub task: &'a str,
+    pub status: Option<WorkspaceStatus>,
+    pub tracking: BranchTracking,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WorkspaceDetails {
     pub created: String,
     pub last_modified: String,
     pub status: String,
     pub files_info: String,
     pub size: String,
+    /// Size breakdown, None while it is being calculated in the background
+    pub disk_usage: Option<DirectorySize>,
     pub recent_commits: Vec<String>,
+    pub workspace_status: Option<WorkspaceStatus>,
+    pub tracking: BranchTracking,
+    /// Changed files in `git status --short` format
+    pub changed_files: Vec<String>,
+    /// Output of the pre-commands run when the workspace was created
+    pub setup_log: Option<String>,
+}
+
+/// Working tree state of a workspace
+///
+/// A file with both staged and unstaged changes is counted in both.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WorkspaceStatus {
+    pub staged: usize,
+    pub unstaged: usize,
+    pub untracked: usize,
+    pub conflicted: usize,
+    pub renamed: usize,
+    /// Git operation in progress (rebase, merge, cherry-pick, bisect, ...)
+    pub operation: Option<&'static str>,
+}
+
+impl WorkspaceStatus {
+    /// Read the status of a repository's working tree (ignored files are not counted)
+    pub fn from_repo(repo: &Repository) -> Result<Self, git2::Error> {
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .include_ignored(false)
+            .renames_head_to_index(true)
+            .renames_index_to_workdir(true);
+
+        let mut status = Self {
+            operation: operation_in_progress(repo),
+            ..Self::default()
+        };
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            let flags = entry.status();
+            if flags.is_conflicted() {
+                status.conflicted += 1;
+                continue;
+            }
+            if flags.intersects(
+                Status::INDEX_NEW
+                    | Status::INDEX_MODIFIED
+                    | Status::INDEX_DELETED
+                    | Status::INDEX_RENAMED
+                    | Status::INDEX_TYPECHANGE,
+            ) {
+                status.staged += 1;
+            }
+            if flags.intersects(
+                Status::WT_MODIFIED
+                    | Status::WT_DELETED
+                    | Status::WT_RENAMED
+                    | Status::WT_TYPECHANGE,
+            ) {
+                status.unstaged += 1;
+            }
+            if flags.is_wt_new() {
+                status.untracked += 1;
+            }
+            if flags.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+                status.renamed += 1;
+            }
+        }
+        Ok(status)
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.staged == 0
+            && self.unstaged == 0
+            && self.untracked == 0
+            && self.conflicted == 0
+            && self.operation.is_none()
+    }
+
+    /// Short state label ("Clean", "Modified", "Conflicted" or "<operation> in progress")
+    pub fn label(&self) -> String {
+        if let Some(operation) = self.operation {
+            format!("{operation} in progress")
+        } else if self.conflicted > 0 {
+            "Conflicted".to_string()
+        } else if self.is_clean() {
+            "Clean".to_string()
+        } else {
+            "Modified".to_string()
+        }
+    }
+
+    /// Count breakdown, e.g. "2 staged, 1 unstaged, 0 untracked, 0 conflicted, 1 renamed"
+    pub fn counts_text(&self) -> String {
+        format!(
+            "{} staged, {} unstaged, {} untracked, {} conflicted, {} renamed",
+            self.staged, self.unstaged, self.untracked, self.conflicted, self.renamed
+        )
+    }
+}
+
+/// Number of commits a branch has that another ref doesn't (ahead) and vice versa (behind)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct AheadBehind {
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+/// Where a workspace branch stands relative to its base branch and its upstream
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BranchTracking {
+    /// Branch the workspace was created from
+    pub base: Option<String>,
+    pub base_counts: Option<AheadBehind>,
+    /// Remote-tracking branch configured as upstream
+    pub upstream: Option<String>,
+    pub upstream_counts: Option<AheadBehind>,
+    /// Upstream exists and contains every local commit
+    pub pushed: bool,
+    /// Every commit of the branch is already in the base branch
+    pub merged: bool,
 }
 
 impl WorkspaceManager {
     pub fn new() -> GitwsResult<Self> {
+        Self::open(".")
+    }
+
+    /// Open the repository at the given path
+    ///
+    /// Works both in a regular repository and in the "bare repo + worktrees" layout,
+    /// where the path may be the bare repository itself or one of its worktrees.
+    pub fn open<P: AsRef<Path>>(path: P) -> GitwsResult<Self> {
         debug!("Initializing WorkspaceManager");
-        let repo = Repository::open(".").map_err(|e| {
+        let repo = Repository::open(path.as_ref()).map_err(|e| {
             error!("Failed to open Git repository: {}", e);
             GitwsError::git(format!("Git repository not found: {e}"))
         })?;
         debug!("Git repository opened successfully");
-        Ok(Self { repo })
+
+        let is_bare = if repo.is_bare() {
+            true
+        } else if repo.is_worktree() {
+            // A linked worktree: check whether the repository it belongs to is bare
+            Repository::open(repo.commondir())
+                .map(|common| common.is_bare())
+                .unwrap_or(false)
+        } else {
+            false
+        };
+        if is_bare {
+            debug!("Bare repository layout detected");
+        }
+
+        Ok(Self {
+            repo,
+            is_bare,
+            size_cache: SizeCache::new(),
+        })
     }
 
     pub fn create_workspace_with_config(
@@ -44,7 +256,34 @@ impl WorkspaceManager {
         branch_prefix: &str,
         copy_files: &[String],
         pre_commands: &[String],
+        copy_source: Option<&str>,
+    ) -> GitwsResult<WorkspaceInfo> {
+        let settings = WorkspaceSettings {
+            base_dir: base_dir.to_string(),
+            branch_prefix: branch_prefix.to_string(),
+            copy_files: copy_files.to_vec(),
+            pre_commands: pre_commands.to_vec(),
+            copy_source: copy_source.map(str::to_string),
+            ..WorkspaceConfig::default().workspace
+        };
+        self.create_workspace(task_name, &settings, None, &mut |line| println!("{line}"))
+    }
+
+    /// Create a workspace from `base` (HEAD of the current worktree if None),
+    /// reporting each step to `progress` instead of printing it
+    pub fn create_workspace(
+        &self,
+        task_name: &str,
+        settings: &WorkspaceSettings,
+        base: Option<&str>,
+        progress: &mut dyn FnMut(&str),
     ) -> GitwsResult<WorkspaceInfo> {
+        let base_dir = settings.base_dir.as_str();
+        let branch_prefix = settings.branch_prefix.as_str();
+        let copy_files = settings.copy_files.as_slice();
+        let pre_commands = settings.pre_commands.as_slice();
+        let copy_source = settings.copy_source.as_deref();
+
         let timestamp = crate::utils::generate_timestamp();
         let workspace_name = format!("{timestamp}-{task_name}");
         let branch_name = if branch_prefix.is_empty() {
@@ -54,14 +293,30 @@ impl WorkspaceManager {
         };
         let workspace_path = format!("{base_dir}/{workspace_name}");
 
+        // Remember what the workspace was created from (used by sync and branch tracking)
+        let (base_ref, base_oid) = match base {
+            Some(base) => {
+                let oid = resolve_commit(&self.repo, base).ok_or_else(|| {
+                    error!("Base ref not found: {}", base);
+                    GitwsError::git(format!("Base ref not found: {base}"))
+                })?;
+                (Some(base.to_string()), Some(oid))
+            }
+            None => (
+                self.current_head_ref(),
+                self.repo.head().ok().and_then(|head| head.target()),
+            ),
+        };
+        let base_commit = base_oid.map(|oid| oid.to_string());
+
         debug!("Creating workspace: {}", workspace_name);
         debug!("Workspace path: {}", workspace_path);
         debug!("Branch name: {}", branch_name);
 
-        println!("🚀 Creating workspace:");
-        println!("  Name: {workspace_name}");
-        println!("  Path: {workspace_path}");
-        println!("  Branch: {branch_name}");
+        progress("🚀 Creating workspace:");
+        progress(&format!("  Name: {workspace_name}"));
+        progress(&format!("  Path: {workspace_path}"));
+        progress(&format!("  Branch: {branch_name}"));
 
         // Create base directory
         if let Some(parent) = Path::new(&workspace_path).parent() {
@@ -95,10 +350,13 @@ impl WorkspaceManager {
             GitwsError::git(format!("HEAD retrieval error: {e}"))
         })?;
 
-        let target_commit = head.target().ok_or_else(|| {
-            error!("Cannot get HEAD commit ID");
-            GitwsError::git("Cannot get HEAD commit ID".to_string())
-        })?;
+        let target_commit = match (base, base_oid) {
+            (Some(_), Some(oid)) => oid,
+            _ => head.target().ok_or_else(|| {
+                error!("Cannot get HEAD commit ID");
+                GitwsError::git("Cannot get HEAD commit ID".to_string())
+            })?,
+        };
 
         let commit = worktree_repo.find_commit(target_commit).map_err(|e| {
             error!("Failed to get commit: {}", e);
@@ -113,6 +371,20 @@ impl WorkspaceManager {
                 GitwsError::git(format!("Branch creation error: {e}"))
             })?;
 
+        if base.is_some() {
+            // The new worktree has no changes of its own yet, so it is safe to force
+            debug!("Checking out base commit: {}", target_commit);
+            worktree_repo
+                .checkout_tree(
+                    commit.as_object(),
+                    Some(git2::build::CheckoutBuilder::new().force()),
+                )
+                .map_err(|e| {
+                    error!("Failed to check out base commit: {}", e);
+                    GitwsError::git(format!("Checkout error: {e}"))
+                })?;
+        }
+
         debug!("Switching to branch: {}", branch_name);
         worktree_repo
             .set_head(&format!("refs/heads/{branch_name}"))
@@ -123,34 +395,256 @@ impl WorkspaceManager {
 
         // File copy processing
         if !copy_files.is_empty() {
-            println!("\n📄 Copying files...");
-            self.copy_files(Path::new("."), Path::new(&workspace_path), copy_files);
+            progress("\n📄 Copying files...");
+            match self.resolve_copy_source(copy_source) {
+                Some(source_path) => {
+                    debug!("Copy source: {}", source_path.display());
+                    self.copy_files(
+                        &source_path,
+                        Path::new(&workspace_path),
+                        copy_files,
+                        progress,
+                    );
+                }
+                None => {
+                    progress(
+                        "  ⚠️  No copy source available (set copy_source in the configuration)",
+                    );
+                }
+            }
         }
 
         // Pre-command execution processing
         if !pre_commands.is_empty() {
-            println!("\n⚡ Executing pre-commands...");
-            self.execute_pre_commands(Path::new(&workspace_path), pre_commands);
+            progress("\n⚡ Executing pre-commands...");
+            let log = self.execute_pre_commands(Path::new(&workspace_path), pre_commands, progress);
+            if let Err(e) = self.save_setup_log(&workspace_name, &log) {
+                warn!("Failed to save setup log: {}", e);
+            }
+        }
+
+        if let Err(e) = self.update_state(|state| {
+            state.workspaces.insert(
+                workspace_name.clone(),
+                WorkspaceMetadata {
+                    base_ref,
+                    base_commit,
+                },
+            );
+        }) {
+            warn!("Failed to record workspace metadata: {}", e);
         }
 
-        println!("\nTo enter the workspace:");
-        println!("  cd {workspace_path}");
+        progress("\nTo enter the workspace:");
+        progress(&format!("  cd {workspace_path}"));
 
         Ok(WorkspaceInfo {
             name: workspace_name,
             path: workspace_path,
             branch: branch_name,
+            locked: None,
+        })
+    }
+
+    fn state_path(&self) -> PathBuf {
+        self.repo.commondir().join("gitws").join("state.yml")
+    }
+
+    /// Load, modify and save the shared gitws state
+    fn update_state<F: FnOnce(&mut GitwsState)>(&self, update: F) -> GitwsResult<()> {
+        let path = self.state_path();
+        let mut state = load_state(&path);
+        update(&mut state);
+        save_state(&path, &state)
+    }
+
+    /// Metadata recorded for a workspace (empty for workspaces created by older versions)
+    pub fn workspace_metadata(&self, workspace_name: &str) -> WorkspaceMetadata {
+        load_state(&self.state_path())
+            .workspaces
+            .remove(workspace_name)
+            .unwrap_or_default()
+    }
+
+    /// TUI settings saved by the previous session
+    pub fn tui_state(&self) -> TuiState {
+        load_state(&self.state_path()).tui
+    }
+
+    pub fn save_tui_state(&self, tui: TuiState) -> GitwsResult<()> {
+        self.update_state(|state| state.tui = tui)
+    }
+
+    /// Remember that the user changed into a workspace
+    pub fn record_visit(&self, workspace_name: &str) -> GitwsResult<()> {
+        self.update_state(|state| state.visits.visit(workspace_name))
+    }
+
+    /// Workspace visited before the current one, for `gitws cd -`
+    pub fn previous_workspace(&self) -> GitwsResult<WorkspaceInfo> {
+        let name = load_state(&self.state_path())
+            .visits
+            .previous
+            .ok_or_else(|| GitwsError::workspace("No previously visited workspace"))?;
+        self.list_workspaces()?
+            .into_iter()
+            .find(|ws| ws.name == name)
+            .ok_or_else(|| {
+                GitwsError::workspace(format!(
+                    "Previously visited workspace no longer exists: {name}"
+                ))
+            })
+    }
+
+    fn setup_log_path(&self, workspace_name: &str) -> PathBuf {
+        self.repo
+            .commondir()
+            .join("gitws")
+            .join("logs")
+            .join(format!("{workspace_name}.log"))
+    }
+
+    fn save_setup_log(&self, workspace_name: &str, log: &str) -> GitwsResult<()> {
+        let path = self.setup_log_path(workspace_name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                error!("Failed to create directory: {} - {}", parent.display(), e);
+                GitwsError::io(format!("Directory creation error: {e}"))
+            })?;
+        }
+        fs::write(&path, log).map_err(|e| {
+            error!("Failed to write setup log: {} - {}", path.display(), e);
+            GitwsError::io(format!("Failed to write setup log: {e}"))
         })
     }
 
-    fn copy_files(&self, source_repo_path: &Path, workspace_path: &Path, copy_files: &[String]) {
+    /// Pre-command output saved when the workspace was created
+    pub fn setup_log(&self, workspace_name: &str) -> Option<String> {
+        fs::read_to_string(self.setup_log_path(workspace_name)).ok()
+    }
+
+    /// Drop the stored metadata and setup log of a workspace that no longer exists
+    pub fn forget_workspace(&self, workspace_name: &str) {
+        let _ = fs::remove_file(self.setup_log_path(workspace_name));
+        if let Err(e) = self.update_state(|state| {
+            state.workspaces.remove(workspace_name);
+        }) {
+            warn!("Failed to remove workspace metadata: {}", e);
+        }
+    }
+
+    /// Branch name (or commit ID when detached) HEAD points to
+    fn current_head_ref(&self) -> Option<String> {
+        let head = self.repo.head().ok()?;
+        if head.is_branch() {
+            head.shorthand().map(str::to_string)
+        } else {
+            head.target().map(|oid| oid.to_string())
+        }
+    }
+
+    /// Base ref of a workspace, falling back to the main repository's HEAD branch
+    pub fn base_ref(&self, workspace: &WorkspaceInfo) -> Option<String> {
+        self.workspace_metadata(&workspace.name)
+            .base_ref
+            .or_else(|| {
+                let main_repo = Repository::open(self.repo.commondir()).ok()?;
+                let head = main_repo.head().ok()?;
+                head.shorthand().map(str::to_string)
+            })
+    }
+
+    /// Ref a workspace is synced onto: the upstream of its base branch if there is one,
+    /// so that fetched changes are picked up, otherwise the base ref itself
+    pub fn sync_target(&self, workspace: &WorkspaceInfo) -> GitwsResult<String> {
+        let base = self.base_ref(workspace).ok_or_else(|| {
+            GitwsError::workspace(format!(
+                "Cannot determine base branch of {}",
+                workspace.name
+            ))
+        })?;
+
+        if let Ok(branch) = self.repo.find_branch(&base, BranchType::Local) {
+            if let Ok(upstream) = branch.upstream() {
+                if let Ok(Some(name)) = upstream.name() {
+                    return Ok(name.to_string());
+                }
+            }
+        }
+        Ok(base)
+    }
+
+    /// Fetch all remotes (no-op for repositories without remotes)
+    pub fn fetch_remotes(&self) -> GitwsResult<()> {
+        let remotes = self.repo.remotes()?;
+        if remotes.is_empty() {
+            debug!("No remotes configured, skipping fetch");
+            return Ok(());
+        }
+
+        println!("🔄 Fetching remotes...");
+        let output = Command::new("git")
+            .args(["fetch", "--all", "--prune"])
+            .current_dir(self.repo.path())
+            .output()
+            .map_err(|e| {
+                error!("Failed to execute git fetch: {}", e);
+                GitwsError::git(format!("git fetch execution error: {e}"))
+            })?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(GitwsError::git(format!(
+                "git fetch failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+
+    /// Resolve the directory files are copied from
+    ///
+    /// `copy_source` may name a worktree or point to a directory. Without it, the current
+    /// working tree is used; a bare repository has none unless gitws runs inside a worktree.
+    fn resolve_copy_source(&self, copy_source: Option<&str>) -> Option<PathBuf> {
+        match copy_source {
+            Some(source) => {
+                if let Ok(worktree) = self.repo.find_worktree(source) {
+                    return Some(worktree.path().to_path_buf());
+                }
+                let path = Path::new(source);
+                if path.is_dir() {
+                    Some(path.to_path_buf())
+                } else {
+                    warn!("Copy source not found: {}", source);
+                    None
+                }
+            }
+            None => {
+                if !self.is_bare {
+                    Some(PathBuf::from("."))
+                } else {
+                    // Inside one of the bare repository's worktrees, copy from it
+                    self.repo.workdir().map(Path::to_path_buf)
+                }
+            }
+        }
+    }
+
+    fn copy_files(
+        &self,
+        source_repo_path: &Path,
+        workspace_path: &Path,
+        copy_files: &[String],
+        progress: &mut dyn FnMut(&str),
+    ) {
         for file_path in copy_files {
             let source_path = source_repo_path.join(file_path);
             let dest_path = workspace_path.join(file_path);
 
             // Skip if source file doesn't exist
             if !source_path.exists() {
-                println!("  ⚠️  File not found: {file_path} (skipped)");
+                progress(&format!("  ⚠️  File not found: {file_path} (skipped)"));
                 continue;
             }
 
@@ -158,11 +652,11 @@ impl WorkspaceManager {
             if let Some(parent) = dest_path.parent() {
                 if !parent.exists() {
                     if let Err(e) = fs::create_dir_all(parent) {
-                        println!(
+                        progress(&format!(
                             "  ❌ Directory creation error: {} - {}",
                             parent.display(),
                             e
-                        );
+                        ));
                         continue;
                     }
                 }
@@ -171,23 +665,31 @@ impl WorkspaceManager {
             // Copy file
             match fs::copy(&source_path, &dest_path) {
                 Ok(_) => {
-                    println!("  ✅ Copy completed: {file_path}");
+                    progress(&format!("  ✅ Copy completed: {file_path}"));
                 }
                 Err(e) => {
-                    println!("  ❌ Copy error: {file_path} - {e}");
+                    progress(&format!("  ❌ Copy error: {file_path} - {e}"));
                 }
             }
         }
     }
 
-    fn execute_pre_commands(&self, workspace_path: &Path, pre_commands: &[String]) {
+    /// Run the pre-commands and return their combined output as a setup log
+    fn execute_pre_commands(
+        &self,
+        workspace_path: &Path,
+        pre_commands: &[String],
+        progress: &mut dyn FnMut(&str),
+    ) -> String {
+        let mut log = String::new();
         for (i, command) in pre_commands.iter().enumerate() {
-            println!(
+            log.push_str(&format!("$ {command}\n"));
+            progress(&format!(
                 "  [{}/{}] Executing: {}",
                 i + 1,
                 pre_commands.len(),
                 command
-            );
+            ));
 
             let output = if cfg!(target_os = "windows") {
                 Command::new("cmd")
@@ -203,31 +705,41 @@ impl WorkspaceManager {
 
             match output {
                 Ok(result) => {
+                    log.push_str(&String::from_utf8_lossy(&result.stdout));
+                    log.push_str(&String::from_utf8_lossy(&result.stderr));
+                    if !result.status.success() {
+                        log.push_str(&format!(
+                            "[exit code: {}]\n",
+                            exit_code_text(&result.status)
+                        ));
+                    }
                     if result.status.success() {
                         // Show stdout if available
                         if !result.stdout.is_empty() {
                             let stdout = String::from_utf8_lossy(&result.stdout);
-                            println!("     Output: {}", stdout.trim());
+                            progress(&format!("     Output: {}", stdout.trim()));
                         }
                     } else {
-                        println!(
+                        progress(&format!(
                             "  ❌ Command execution failed: {} (exit code: {:?})",
                             command,
                             result.status.code()
-                        );
+                        ));
 
                         // Show stderr if available
                         if !result.stderr.is_empty() {
                             let stderr = String::from_utf8_lossy(&result.stderr);
-                            println!("     Error: {}", stderr.trim());
+                            progress(&format!("     Error: {}", stderr.trim()));
                         }
                     }
                 }
                 Err(e) => {
-                    println!("  ❌ Command execution error: {command} - {e}");
+                    progress(&format!("  ❌ Command execution error: {command} - {e}"));
+                    log.push_str(&format!("[execution error: {e}]\n"));
                 }
             }
         }
+        log
     }
 
     pub fn list_workspaces(&self) -> GitwsResult<Vec<WorkspaceInfo>> {
@@ -266,19 +778,99 @@ impl WorkspaceManager {
                         name: worktree_name.to_string(),
                         path: path.to_string(),
                         branch: branch_name,
+                        locked: lock_reason(&worktree),
                     });
                 }
             }
         }
 
-        // Exclude main worktree (typically the main branch working directory)
-        workspace_list.retain(|ws| ws.name != "main" && !ws.path.ends_with("/.git"));
+        // Exclude main worktree (typically the main branch working directory).
+        // A bare repository has no main worktree, so every worktree is a workspace.
+        if !self.is_bare {
+            workspace_list.retain(|ws| ws.name != "main" && !ws.path.ends_with("/.git"));
+        }
 
         Ok(workspace_list)
     }
 
+    /// Find a single workspace by name, branch, task name or fuzzy match
+    pub fn find_workspace(&self, query: &str) -> GitwsResult<WorkspaceInfo> {
+        let mut matches = match_workspaces(self.list_workspaces()?, query);
+        match matches.len() {
+            0 => Err(GitwsError::workspace(format!(
+                "No workspace matches: {query}"
+            ))),
+            1 => Ok(matches.remove(0)),
+            _ => Err(GitwsError::workspace(format!(
+                "Multiple workspaces match '{query}': {}",
+                matches
+                    .iter()
+                    .map(|ws| ws.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))),
+        }
+    }
+
+    /// Run a command inside a workspace and return its exit code
+    pub fn execute_in_workspace(
+        &self,
+        workspace: &WorkspaceInfo,
+        command: &[String],
+        env: &BTreeMap<String, String>,
+    ) -> GitwsResult<i32> {
+        debug!("Executing in workspace {}: {:?}", workspace.name, command);
+        let status = build_workspace_command(workspace, command, env)?
+            .status()
+            .map_err(|e| {
+                error!("Failed to execute command: {:?} - {}", command, e);
+                GitwsError::workspace(format!("Command execution error: {e}"))
+            })?;
+        Ok(exit_code(&status))
+    }
+
+    /// Lock a workspace's worktree so that gc, the TUI and `git worktree prune` leave it alone
+    pub fn lock_workspace(
+        &self,
+        workspace: &WorkspaceInfo,
+        reason: Option<&str>,
+    ) -> GitwsResult<()> {
+        if workspace.is_locked() {
+            return Err(GitwsError::workspace(format!(
+                "Workspace is already locked: {}",
+                workspace.name
+            )));
+        }
+        let worktree = self.repo.find_worktree(&workspace.name).map_err(|e| {
+            error!("Failed to find worktree: {} - {}", workspace.name, e);
+            GitwsError::git(format!("Worktree not found: {} - {e}", workspace.name))
+        })?;
+        worktree.lock(reason).map_err(|e| {
+            error!("Failed to lock worktree: {} - {}", workspace.name, e);
+            GitwsError::git(format!("Worktree lock error: {e}"))
+        })
+    }
+
+    pub fn unlock_workspace(&self, workspace: &WorkspaceInfo) -> GitwsResult<()> {
+        if !workspace.is_locked() {
+            return Err(GitwsError::workspace(format!(
+                "Workspace is not locked: {}",
+                workspace.name
+            )));
+        }
+        let worktree = self.repo.find_worktree(&workspace.name).map_err(|e| {
+            error!("Failed to find worktree: {} - {}", workspace.name, e);
+            GitwsError::git(format!("Worktree not found: {} - {e}", workspace.name))
+        })?;
+        worktree.unlock().map_err(|e| {
+            error!("Failed to unlock worktree: {} - {}", workspace.name, e);
+            GitwsError::git(format!("Worktree unlock error: {e}"))
+        })
+    }
+
+    /// Remove a workspace and its branch; locked workspaces are refused unless `force` is set
     #[allow(dead_code)]
-    pub fn remove_workspace(&self, workspace_name: &str) -> GitwsResult<()> {
+    pub fn remove_workspace(&self, workspace_name: &str, force: bool) -> GitwsResult<()> {
         debug!("Deleting workspace: {}", workspace_name);
         // First identify branch name associated with workspace
         let mut branch_to_delete = None;
@@ -290,15 +882,30 @@ impl WorkspaceManager {
                 if workspace.name == workspace_name {
                     debug!("Target branch for deletion: {}", workspace.branch.clone());
                     branch_to_delete = Some(workspace.branch.clone());
+                    if workspace.is_locked() && !force {
+                        return Err(GitwsError::workspace(format!(
+                            "Workspace is locked: {}{}",
+                            workspace_name,
+                            lock_suffix(&workspace)
+                        )));
+                    }
                     break;
                 }
             }
         }
 
-        // Delete workspace using git worktree remove command
+        // Delete workspace using git worktree remove command; a second --force removes a
+        // locked worktree, which keeps its lock if the removal fails
+        let force_args: &[&str] = if force {
+            &["--force", "--force"]
+        } else {
+            &["--force"]
+        };
         debug!("Deleting workspace with git worktree command");
         let output = std::process::Command::new("git")
-            .args(["worktree", "remove", "--force", workspace_name])
+            .args(["worktree", "remove"])
+            .args(force_args)
+            .arg(workspace_name)
             .output()
             .map_err(|e| {
                 error!("git worktree removeコマンド実行に失敗しました: {}", e);
@@ -324,6 +931,7 @@ impl WorkspaceManager {
                 .args(["branch", "-D", workspace_name])
                 .output();
 
+            self.forget_workspace(workspace_name);
             return Ok(());
         }
 
@@ -338,7 +946,9 @@ impl WorkspaceManager {
         for path in &potential_paths {
             debug!("パスでの削除を試行: {}", path);
             let output = std::process::Command::new("git")
-                .args(["worktree", "remove", "--force", path])
+                .args(["worktree", "remove"])
+                .args(force_args)
+                .arg(path)
                 .output()
                 .map_err(|e| {
                     error!("git worktree removeコマンド実行に失敗しました: {}", e);
@@ -354,6 +964,7 @@ impl WorkspaceManager {
                         .args(["branch", "-D", branch_name])
                         .output();
                 }
+                self.forget_workspace(workspace_name);
                 return Ok(());
             }
         }
@@ -376,6 +987,7 @@ impl WorkspaceManager {
 
         if found_and_removed {
             warn!("ファイルシステムからの直接削除が成功しました");
+            self.forget_workspace(workspace_name);
             Ok(())
         } else {
             error!("ワークスペースの削除に失敗しました: {}", workspace_name);
@@ -393,7 +1005,7 @@ impl WorkspaceManager {
         let mut deleted_count = 0;
 
         for workspace_name in workspace_names {
-            match self.remove_workspace(workspace_name) {
+            match self.remove_workspace(workspace_name, false) {
                 Ok(()) => {
                     debug!("Successfully deleted workspace: {}", workspace_name);
                     deleted_count += 1;
@@ -430,6 +1042,192 @@ impl WorkspaceManager {
         }
     }
 
+    /// Give a workspace a new task name: renames its branch, moves the worktree
+    /// directory and updates git's worktree admin files and the stored metadata.
+    /// The timestamp prefix of the workspace name is kept.
+    pub fn rename_workspace(
+        &self,
+        workspace: &WorkspaceInfo,
+        new_task_name: &str,
+    ) -> GitwsResult<WorkspaceInfo> {
+        // Like `git worktree move`, leave locked workspaces where they are
+        if workspace.is_locked() {
+            return Err(GitwsError::workspace(format!(
+                "Workspace is locked: {}{}",
+                workspace.name,
+                lock_suffix(workspace)
+            )));
+        }
+        if new_task_name.is_empty()
+            || new_task_name.contains(char::is_whitespace)
+            || new_task_name.contains('/')
+        {
+            return Err(GitwsError::workspace(format!(
+                "Invalid task name: '{new_task_name}' (must be non-empty without spaces or '/')"
+            )));
+        }
+
+        let old_task_name = workspace.task_name();
+        let prefix = &workspace.name[..workspace.name.len() - old_task_name.len()];
+        let new_name = format!("{prefix}{new_task_name}");
+        if new_name == workspace.name {
+            return Err(GitwsError::workspace(format!(
+                "Workspace is already named {new_name}"
+            )));
+        }
+        // Branches created by gitws end with the task name; others are left alone
+        let new_branch = match workspace.branch.strip_suffix(old_task_name) {
+            Some(branch_prefix) => format!("{branch_prefix}{new_task_name}"),
+            None => workspace.branch.clone(),
+        };
+        let old_path = Path::new(&workspace.path);
+        let new_path = old_path.with_file_name(&new_name);
+        let admin_dir = self.repo.commondir().join("worktrees");
+
+        debug!("Renaming workspace {} to {}", workspace.name, new_name);
+
+        // Refuse to overwrite anything
+        if new_path.exists() {
+            return Err(GitwsError::workspace(format!(
+                "Target directory already exists: {}",
+                new_path.display()
+            )));
+        }
+        if self.repo.find_worktree(&new_name).is_ok() || admin_dir.join(&new_name).exists() {
+            return Err(GitwsError::workspace(format!(
+                "Worktree already exists: {new_name}"
+            )));
+        }
+        if new_branch != workspace.branch
+            && self
+                .repo
+                .find_branch(&new_branch, BranchType::Local)
+                .is_ok()
+        {
+            return Err(GitwsError::workspace(format!(
+                "Branch already exists: {new_branch}"
+            )));
+        }
+        self.repo.find_worktree(&workspace.name).map_err(|e| {
+            error!("Failed to find worktree: {} - {}", workspace.name, e);
+            GitwsError::git(format!("Worktree not found: {}", workspace.name))
+        })?;
+        let old_admin = admin_dir.join(&workspace.name);
+        let new_admin = admin_dir.join(&new_name);
+
+        // Rename the branch first; libgit2 also updates the HEAD of the worktree
+        if new_branch != workspace.branch {
+            debug!("Renaming branch {} to {}", workspace.branch, new_branch);
+            self.repo
+                .find_branch(&workspace.branch, BranchType::Local)
+                .and_then(|mut branch| branch.rename(&new_branch, false))
+                .map_err(|e| {
+                    error!("Failed to rename branch: {} - {}", workspace.branch, e);
+                    GitwsError::git(format!("Branch rename error: {e}"))
+                })?;
+        }
+
+        // git keeps absolute paths in the admin files
+        let new_path = new_path
+            .parent()
+            .and_then(|parent| fs::canonicalize(parent).ok())
+            .map_or(new_path.clone(), |parent| parent.join(&new_name));
+        // Kept to undo the move if a later step fails
+        let old_gitdir = fs::read(old_admin.join("gitdir")).unwrap_or_default();
+        let old_dot_git = fs::read(old_path.join(".git")).unwrap_or_default();
+
+        debug!(
+            "Moving worktree: {} -> {}",
+            old_path.display(),
+            new_path.display()
+        );
+        // Move the checkout and point git's admin files at the new locations
+        // (what `git worktree move` does); each completed step is undone on failure
+        let mut moved_checkout = false;
+        let mut moved_admin = false;
+        let result = (|| -> GitwsResult<()> {
+            fs::rename(old_path, &new_path).map_err(|e| {
+                error!("Failed to move worktree: {}", e);
+                GitwsError::io(format!("Worktree move error: {e}"))
+            })?;
+            moved_checkout = true;
+            fs::rename(&old_admin, &new_admin)
+                .inspect(|()| moved_admin = true)
+                .and_then(|()| {
+                    fs::write(
+                        new_admin.join("gitdir"),
+                        format!("{}\n", new_path.join(".git").display()),
+                    )
+                })
+                .and_then(|()| {
+                    fs::write(
+                        new_path.join(".git"),
+                        format!("gitdir: {}\n", new_admin.display()),
+                    )
+                })
+                .map_err(|e| {
+                    error!("Failed to update worktree admin files: {}", e);
+                    GitwsError::io(format!("Worktree admin file update error: {e}"))
+                })?;
+            self.repo
+                .find_worktree(&new_name)
+                .and_then(|worktree| worktree.validate())
+                .map_err(|e| {
+                    error!("Renamed worktree is invalid: {}", e);
+                    GitwsError::git(format!("Renamed worktree is invalid: {e}"))
+                })
+        })();
+        if let Err(e) = result {
+            // Put everything back in reverse order so the workspace stays usable
+            if moved_admin {
+                let _ = fs::write(new_admin.join("gitdir"), &old_gitdir);
+                let _ = fs::rename(&new_admin, &old_admin);
+            }
+            if moved_checkout {
+                let _ = fs::write(new_path.join(".git"), &old_dot_git);
+                let _ = fs::rename(&new_path, old_path);
+            }
+            if new_branch != workspace.branch {
+                if let Ok(mut branch) = self.repo.find_branch(&new_branch, BranchType::Local) {
+                    let _ = branch.rename(&workspace.branch, false);
+                }
+            }
+            return Err(e);
+        }
+
+        // libgit2 created a branch named after the workspace along with the worktree; nothing
+        // uses it and remove_workspace only deletes it under the workspace's current name
+        if workspace.branch != workspace.name {
+            if let Ok(mut branch) = self.repo.find_branch(&workspace.name, BranchType::Local) {
+                debug!("Deleting auto-created branch: {}", workspace.name);
+                if let Err(e) = branch.delete() {
+                    warn!("Failed to delete branch {}: {}", workspace.name, e);
+                }
+            }
+        }
+
+        // Carry the metadata and setup log over to the new name
+        let _ = fs::rename(
+            self.setup_log_path(&workspace.name),
+            self.setup_log_path(&new_name),
+        );
+        if let Err(e) = self.update_state(|state| {
+            if let Some(metadata) = state.workspaces.remove(&workspace.name) {
+                state.workspaces.insert(new_name.clone(), metadata);
+            }
+            state.visits.rename(&workspace.name, &new_name);
+        }) {
+            warn!("Failed to update workspace metadata: {}", e);
+        }
+
+        Ok(WorkspaceInfo {
+            name: new_name,
+            path: new_path.to_string_lossy().into_owned(),
+            branch: new_branch,
+            locked: workspace.locked.clone(),
+        })
+    }
+
     pub fn get_workspace_details(
         &self,
         workspace_info: &WorkspaceInfo,
@@ -489,69 +1287,42 @@ impl WorkspaceManager {
         };
 
         // Git status情報を取得
-        let (status, files_info) = if workspace_path.exists() {
-            match Repository::open(workspace_path) {
-                Ok(repo) => match repo.statuses(None) {
-                    Ok(statuses) => {
-                        let mut modified_count = 0;
-                        let mut untracked_count = 0;
-                        let mut tracked_count = 0;
-
-                        for status_entry in statuses.iter() {
-                            let status = status_entry.status();
-                            if status.contains(git2::Status::WT_MODIFIED)
-                                || status.contains(git2::Status::WT_DELETED)
-                                || status.contains(git2::Status::INDEX_MODIFIED)
-                                || status.contains(git2::Status::INDEX_DELETED)
-                            {
-                                modified_count += 1;
-                            } else if status.contains(git2::Status::WT_NEW) {
-                                untracked_count += 1;
-                            } else {
-                                tracked_count += 1;
-                            }
-                        }
-
-                        let status_text = if modified_count > 0 {
-                            format!("Modified ({modified_count} files)")
-                        } else {
-                            "Clean".to_string()
-                        };
-
-                        let files_text =
-                            format!("{tracked_count} tracked, {untracked_count} untracked");
-                        (status_text, files_text)
-                    }
-                    Err(_) => ("不明".to_string(), "不明".to_string()),
-                },
-                Err(_) => (
-                    "Gitリポジトリではありません".to_string(),
-                    "不明".to_string(),
-                ),
-            }
+        let workspace_status = if workspace_path.exists() {
+            Repository::open(workspace_path)
+                .map(|repo| WorkspaceStatus::from_repo(&repo))
+                .map_err(|_| "Gitリポジトリではありません")
         } else {
-            (
-                "ワークスペースが存在しません".to_string(),
-                "不明".to_string(),
-            )
+            Err("ワークスペースが存在しません")
+        };
+        let changed_files = Repository::open(workspace_path)
+            .and_then(|repo| changed_files(&repo))
+            .unwrap_or_default();
+        let (status, files_info, workspace_status) = match workspace_status {
+            Ok(Ok(workspace_status)) => (
+                workspace_status.label(),
+                workspace_status.counts_text(),
+                Some(workspace_status),
+            ),
+            Ok(Err(_)) => ("不明".to_string(), "不明".to_string(), None),
+            Err(message) => (message.to_string(), "不明".to_string(), None),
         };
 
-        // ディレクトリサイズを取得
-        let size = if workspace_path.exists() {
-            match Self::calculate_directory_size(workspace_path) {
-                Ok(size_bytes) => {
-                    if size_bytes < 1024 {
-                        format!("{size_bytes} B")
-                    } else if size_bytes < 1024 * 1024 {
-                        format!("{:.1} KB", size_bytes as f64 / 1024.0)
-                    } else {
-                        format!("{:.1} MB", size_bytes as f64 / (1024.0 * 1024.0))
-                    }
-                }
-                Err(_) => "不明".to_string(),
+        // ディレクトリサイズを取得（バックグラウンドで計算してキャッシュ）
+        let (size, disk_usage) = if workspace_path.exists() {
+            match self.size_cache.get(workspace_path) {
+                SizeState::Ready(usage) => (
+                    format!(
+                        "{} (disk: {})",
+                        format_size(usage.apparent_bytes),
+                        format_size(usage.disk_bytes)
+                    ),
+                    Some(usage),
+                ),
+                SizeState::Calculating => ("計算中...".to_string(), None),
+                SizeState::Failed(_) => ("不明".to_string(), None),
             }
         } else {
-            "不明".to_string()
+            ("不明".to_string(), None)
         };
 
         // 最近のコミット履歴を取得
@@ -604,39 +1375,355 @@ impl WorkspaceManager {
             vec!["ワークスペースが存在しません".to_string()]
         };
 
+        let tracking = self.get_branch_tracking(workspace_info);
+
         Ok(WorkspaceDetails {
             created,
             last_modified,
             status,
             files_info,
             size,
+            disk_usage,
             recent_commits,
+            workspace_status,
+            tracking,
+            changed_files,
+            setup_log: self.setup_log(&workspace_info.name),
+        })
+    }
+
+    /// Changes of a workspace against its base branch or HEAD
+    pub fn get_workspace_diff(
+        &self,
+        workspace_info: &WorkspaceInfo,
+        target: DiffTarget,
+    ) -> GitwsResult<WorkspaceDiff> {
+        let base = self.base_ref(workspace_info);
+        workspace_diff(Path::new(&workspace_info.path), base.as_deref(), target).map_err(|e| {
+            error!("Failed to compute diff of {}: {}", workspace_info.name, e);
+            GitwsError::git(format!("Diff error: {}", e.message()))
+        })
+    }
+
+    /// Open another handle to the same repository, e.g. for use on another thread.
+    /// The size cache is shared between handles.
+    pub fn try_clone(&self) -> GitwsResult<Self> {
+        let repo = Repository::open(self.repo.path()).map_err(|e| {
+            error!("Failed to reopen repository: {}", e);
+            GitwsError::git(format!("Repository open error: {e}"))
+        })?;
+        Ok(Self {
+            repo,
+            is_bare: self.is_bare,
+            size_cache: self.size_cache.clone(),
         })
     }
 
-    fn calculate_directory_size(path: &Path) -> Result<u64, std::io::Error> {
-        let mut total_size = 0;
+    /// Repository gitws was started in: a linked worktree, the main working tree or a bare repository
+    pub fn repository(&self) -> &Repository {
+        &self.repo
+    }
 
-        if path.is_dir() {
-            for entry in fs::read_dir(path)? {
-                let entry = entry?;
-                let metadata = entry.metadata()?;
+    pub fn size_cache(&self) -> SizeCache {
+        self.size_cache.clone()
+    }
 
-                if metadata.is_dir() {
-                    // .gitディレクトリはスキップ
-                    if entry.file_name() != ".git" {
-                        total_size += Self::calculate_directory_size(&entry.path())?;
-                    }
-                } else {
-                    total_size += metadata.len();
+    /// Working tree status of a workspace (None if it cannot be opened)
+    pub fn get_workspace_status(&self, workspace_info: &WorkspaceInfo) -> Option<WorkspaceStatus> {
+        let repo = Repository::open(&workspace_info.path).ok()?;
+        WorkspaceStatus::from_repo(&repo)
+            .map_err(|e| warn!("Failed to get status of {}: {}", workspace_info.name, e))
+            .ok()
+    }
+
+    /// Unix time of the latest activity in a workspace: last commit, index update
+    /// or change of a top-level entry
+    pub fn get_last_activity(&self, workspace_info: &WorkspaceInfo) -> Option<i64> {
+        let repo = Repository::open(&workspace_info.path).ok()?;
+        let mtime = |path: &Path| -> Option<i64> {
+            let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+            let duration = modified.duration_since(std::time::UNIX_EPOCH).ok()?;
+            Some(duration.as_secs() as i64)
+        };
+
+        let commit_time = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .ok()
+            .map(|commit| commit.time().seconds());
+        let index_time = mtime(&repo.path().join("index"));
+        let dir_time = mtime(Path::new(&workspace_info.path));
+        [commit_time, index_time, dir_time]
+            .into_iter()
+            .flatten()
+            .max()
+    }
+
+    /// Compare a workspace branch with its base branch and upstream
+    ///
+    /// Fields that cannot be determined (detached HEAD, missing base, no upstream) are left empty.
+    pub fn get_branch_tracking(&self, workspace_info: &WorkspaceInfo) -> BranchTracking {
+        let mut tracking = BranchTracking::default();
+
+        let Ok(repo) = Repository::open(&workspace_info.path) else {
+            return tracking;
+        };
+        let Ok(head) = repo.head() else {
+            return tracking;
+        };
+        let Some(head_oid) = head.target() else {
+            return tracking;
+        };
+
+        // Base branch (skipped for the base branch's own worktree)
+        if let Some(base) = self
+            .base_ref(workspace_info)
+            .filter(|base| head.shorthand() != Some(base.as_str()))
+        {
+            if let Some(base_oid) = resolve_commit(&repo, &base) {
+                let counts = ahead_behind(&repo, head_oid, base_oid);
+                // A branch still at the commit it was created from has no work to be merged.
+                // Without a recorded creation commit, fall back to the base tip.
+                let start_oid = self
+                    .workspace_metadata(&workspace_info.name)
+                    .base_commit
+                    .and_then(|oid| Oid::from_str(&oid).ok())
+                    .unwrap_or(base_oid);
+                tracking.merged = counts.is_some_and(|c| c.ahead == 0) && head_oid != start_oid;
+                tracking.base_counts = counts;
+            }
+            tracking.base = Some(base);
+        }
+
+        // Upstream
+        if head.is_branch() {
+            if let Ok(upstream) = Branch::wrap(head).upstream() {
+                let name = upstream.name().ok().flatten().map(str::to_string);
+                if let Some(upstream_oid) = upstream.get().target() {
+                    let counts = ahead_behind(&repo, head_oid, upstream_oid);
+                    tracking.pushed = counts.is_some_and(|c| c.ahead == 0);
+                    tracking.upstream_counts = counts;
                 }
+                tracking.upstream = name;
             }
         }
 
-        Ok(total_size)
+        tracking
+    }
+}
+
+/// Lock reason of a worktree, or None when it is not locked
+pub fn lock_reason(worktree: &Worktree) -> Option<String> {
+    match worktree.is_locked() {
+        Ok(WorktreeLockStatus::Locked(reason)) => {
+            Some(reason.unwrap_or_default().trim().to_string())
+        }
+        _ => None,
     }
 }
 
+/// " (reason)" for messages about a locked workspace
+fn lock_suffix(workspace: &WorkspaceInfo) -> String {
+    match workspace.locked.as_deref() {
+        Some("") | None => String::new(),
+        Some(reason) => format!(" ({reason})"),
+    }
+}
+
+fn resolve_commit(repo: &Repository, spec: &str) -> Option<Oid> {
+    repo.revparse_single(spec)
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id())
+        .ok()
+}
+
+fn ahead_behind(repo: &Repository, local: Oid, other: Oid) -> Option<AheadBehind> {
+    repo.graph_ahead_behind(local, other)
+        .map(|(ahead, behind)| AheadBehind { ahead, behind })
+        .ok()
+}
+
+/// Changed files in `git status --short` format ("XY path"), at most MAX_CHANGED_FILES
+pub fn changed_files(repo: &Repository) -> Result<Vec<String>, git2::Error> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .include_ignored(false)
+        .renames_head_to_index(true);
+
+    let statuses = repo.statuses(Some(&mut options))?;
+    let files = statuses
+        .iter()
+        .take(MAX_CHANGED_FILES)
+        .map(|entry| {
+            let flags = entry.status();
+            let code = if flags.is_conflicted() {
+                "UU".to_string()
+            } else if flags.is_wt_new() {
+                "??".to_string()
+            } else {
+                let index = if flags.is_index_new() {
+                    'A'
+                } else if flags.is_index_modified() {
+                    'M'
+                } else if flags.is_index_deleted() {
+                    'D'
+                } else if flags.is_index_renamed() {
+                    'R'
+                } else if flags.is_index_typechange() {
+                    'T'
+                } else {
+                    ' '
+                };
+                let worktree = if flags.is_wt_modified() {
+                    'M'
+                } else if flags.is_wt_deleted() {
+                    'D'
+                } else if flags.is_wt_typechange() {
+                    'T'
+                } else {
+                    ' '
+                };
+                format!("{index}{worktree}")
+            };
+
+            let path = match entry.head_to_index().filter(|_| flags.is_index_renamed()) {
+                Some(delta) => format!(
+                    "{} -> {}",
+                    delta.old_file().path().unwrap_or(Path::new("")).display(),
+                    delta.new_file().path().unwrap_or(Path::new("")).display()
+                ),
+                None => entry.path().unwrap_or("").to_string(),
+            };
+            format!("{code} {path}")
+        })
+        .collect();
+    Ok(files)
+}
+
+/// Name of the git operation in progress in a repository (rebase, merge, ...), if any
+pub fn operation_in_progress(repo: &Repository) -> Option<&'static str> {
+    match repo.state() {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some("merge"),
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some("revert"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("cherry-pick"),
+        RepositoryState::Bisect => Some("bisect"),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => Some("rebase"),
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some("am"),
+    }
+}
+
+/// Select the workspaces matching a query, best match first
+///
+/// An exact name or branch match wins outright, then an exact task name match.
+/// Otherwise every workspace whose name or branch fuzzy-matches the query is returned.
+pub fn match_workspaces(workspaces: Vec<WorkspaceInfo>, query: &str) -> Vec<WorkspaceInfo> {
+    if let Some(ws) = workspaces
+        .iter()
+        .find(|ws| ws.name == query || ws.branch == query)
+    {
+        return vec![ws.clone()];
+    }
+
+    let task_matches: Vec<WorkspaceInfo> = workspaces
+        .iter()
+        .filter(|ws| ws.task_name() == query)
+        .cloned()
+        .collect();
+    if !task_matches.is_empty() {
+        return task_matches;
+    }
+
+    let mut scored: Vec<(i64, WorkspaceInfo)> = workspaces
+        .into_iter()
+        .filter_map(|ws| {
+            let score = [
+                crate::fuzzy::fuzzy_match(query, ws.task_name()),
+                crate::fuzzy::fuzzy_match(query, &ws.branch),
+            ]
+            .into_iter()
+            .flatten()
+            .map(|m| m.score)
+            .max()?;
+            Some((score, ws))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored.into_iter().map(|(_, ws)| ws).collect()
+}
+
+/// Keep the workspaces whose task name or branch fuzzy-matches the pattern
+pub fn filter_workspaces(workspaces: Vec<WorkspaceInfo>, pattern: &str) -> Vec<WorkspaceInfo> {
+    workspaces
+        .into_iter()
+        .filter(|ws| {
+            crate::fuzzy::fuzzy_match(pattern, ws.task_name()).is_some()
+                || crate::fuzzy::fuzzy_match(pattern, &ws.branch).is_some()
+        })
+        .collect()
+}
+
+/// Build a command that runs inside a workspace with the workspace environment
+///
+/// A single argument is run through the shell so that `"git fetch && git rebase"` works,
+/// several arguments are executed directly.
+pub fn build_workspace_command(
+    workspace: &WorkspaceInfo,
+    command: &[String],
+    env: &BTreeMap<String, String>,
+) -> GitwsResult<Command> {
+    let mut cmd = match command {
+        [] => return Err(GitwsError::workspace("No command specified")),
+        [single] => {
+            if cfg!(target_os = "windows") {
+                let mut cmd = Command::new("cmd");
+                cmd.args(["/C", single]);
+                cmd
+            } else {
+                let mut cmd = Command::new("sh");
+                cmd.args(["-c", single]);
+                cmd
+            }
+        }
+        [program, args @ ..] => {
+            let mut cmd = Command::new(program);
+            cmd.args(args);
+            cmd
+        }
+    };
+    cmd.current_dir(&workspace.path)
+        .envs(env)
+        .envs(workspace.env_vars());
+    Ok(cmd)
+}
+
+/// Exit code of a finished process (128 + signal number when killed by a signal)
+pub fn exit_code(status: &std::process::ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
+
+/// Exit code as `exit_code` reports it, naming the signal of killed processes
+pub fn exit_code_text(status: &std::process::ExitStatus) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("{}, killed by signal {signal}", exit_code(status));
+        }
+    }
+    exit_code(status).to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -655,7 +1742,7 @@ mod tests {
     // テスト完了時にワークスペースをクリーンアップ
     fn cleanup_test_workspace(manager: &WorkspaceManager, workspace_name: &str) {
         // git2を使ってworktreeを削除
-        let _ = manager.remove_workspace(workspace_name);
+        let _ = manager.remove_workspace(workspace_name, true);
     }
 
     // すべてのテスト用ワークスペースを一括削除
@@ -666,7 +1753,7 @@ mod tests {
                 for workspace in workspaces {
                     // test-workspacesディレクトリ内のワークスペースを削除
                     if workspace.path.contains("test-workspaces") {
-                        let _ = manager.remove_workspace(&workspace.name);
+                        let _ = manager.remove_workspace(&workspace.name, true);
                     }
                 }
             }
@@ -697,8 +1784,14 @@ mod tests {
             base_dir: &str,
             branch_prefix: &str,
         ) -> GitwsResult<WorkspaceInfo> {
-            self.manager
-                .create_workspace_with_config(task_name, base_dir, branch_prefix, &[], &[])
+            self.manager.create_workspace_with_config(
+                task_name,
+                base_dir,
+                branch_prefix,
+                &[],
+                &[],
+                None,
+            )
         }
     }
 
@@ -706,7 +1799,7 @@ mod tests {
         fn drop(&mut self) {
             // テスト終了時に作成されたワークスペースを確実に削除
             for workspace_name in &self.workspace_names {
-                let _ = self.manager.remove_workspace(workspace_name);
+                let _ = self.manager.remove_workspace(workspace_name, true);
             }
         }
     }
@@ -731,6 +1824,7 @@ mod tests {
             name: "test-workspace".to_string(),
             path: "/path/to/workspace".to_string(),
             branch: "work/test".to_string(),
+            locked: None,
         };
 
         assert_eq!(info.name, "test-workspace");
@@ -844,6 +1938,7 @@ mod tests {
             name: "test".to_string(),
             path: "/path".to_string(),
             branch: "branch".to_string(),
+            locked: None,
         };
         let debug_str = format!("{info:?}");
         assert!(debug_str.contains("WorkspaceInfo"));
@@ -863,6 +1958,7 @@ mod tests {
                 "test/",
                 &[],
                 &[],
+                None,
             );
             // 権限エラーなどが発生する可能性があるが、適切にエラーハンドリングされる
             match result {
@@ -993,10 +2089,195 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_workspace_from_base_ref_with_progress() {
+        if let Ok(mut guard) = TestWorkspaceGuard::new() {
+            let task_name = generate_test_workspace_name("base-ref");
+            let settings = WorkspaceSettings {
+                base_dir: "../test-workspaces".to_string(),
+                branch_prefix: "test/".to_string(),
+                ..WorkspaceConfig::default().workspace
+            };
+
+            // ベースrefを指定して作成し、進捗は標準出力ではなくコールバックに渡される
+            let mut lines = Vec::new();
+            let workspace = guard
+                .manager
+                .create_workspace(&task_name, &settings, Some("HEAD~1"), &mut |line| {
+                    lines.push(line.to_string())
+                })
+                .unwrap();
+            guard.add_workspace(workspace.name.clone());
+
+            assert_eq!(lines[0], "🚀 Creating workspace:");
+            let base = resolve_commit(&guard.manager.repo, "HEAD~1").unwrap();
+            let worktree_repo = Repository::open(&workspace.path).unwrap();
+            assert_eq!(worktree_repo.head().unwrap().target(), Some(base));
+            // 作業ツリーもベースコミットの内容になっている
+            assert!(WorkspaceStatus::from_repo(&worktree_repo)
+                .unwrap()
+                .is_clean());
+
+            let metadata = guard.manager.workspace_metadata(&workspace.name);
+            assert_eq!(metadata.base_ref.as_deref(), Some("HEAD~1"));
+            assert_eq!(metadata.base_commit, Some(base.to_string()));
+
+            // 存在しないベースrefはエラー
+            assert!(guard
+                .manager
+                .create_workspace(&task_name, &settings, Some("no-such-ref"), &mut |_| {})
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_lock_workspace() {
+        if let Ok(mut guard) = TestWorkspaceGuard::new() {
+            let workspace = guard
+                .create_workspace(
+                    &generate_test_workspace_name("lock"),
+                    "../test-workspaces",
+                    "test/",
+                )
+                .unwrap();
+            guard.add_workspace(workspace.name.clone());
+            let find = |manager: &WorkspaceManager| {
+                manager
+                    .list_workspaces()
+                    .unwrap()
+                    .into_iter()
+                    .find(|ws| ws.name == workspace.name)
+                    .unwrap()
+            };
+            assert!(!workspace.is_locked());
+
+            // ロックの理由が一覧に反映される
+            guard
+                .manager
+                .lock_workspace(&workspace, Some("agent running"))
+                .unwrap();
+            let locked = find(&guard.manager);
+            assert_eq!(locked.locked.as_deref(), Some("agent running"));
+            assert!(guard.manager.lock_workspace(&locked, None).is_err());
+
+            // ロック中は名前の変更もできない
+            let result = guard
+                .manager
+                .rename_workspace(&locked, "renamed-while-locked");
+            assert!(result.unwrap_err().to_string().contains("agent running"));
+            assert!(Path::new(&workspace.path).exists());
+
+            // ロック中は force なしでは削除できない
+            let result = guard.manager.remove_workspace(&workspace.name, false);
+            assert!(result.unwrap_err().to_string().contains("agent running"));
+            assert!(Path::new(&workspace.path).exists());
+
+            guard.manager.unlock_workspace(&locked).unwrap();
+            let unlocked = find(&guard.manager);
+            assert_eq!(unlocked.locked, None);
+            assert!(guard.manager.unlock_workspace(&unlocked).is_err());
+
+            // force を指定するとロックされたまま削除する
+            guard.manager.lock_workspace(&unlocked, None).unwrap();
+            assert_eq!(find(&guard.manager).locked.as_deref(), Some(""));
+            guard
+                .manager
+                .remove_workspace(&workspace.name, true)
+                .unwrap();
+            assert!(!Path::new(&workspace.path).exists());
+        }
+    }
+
+    #[test]
+    fn test_rename_workspace() {
+        if let Ok(mut guard) = TestWorkspaceGuard::new() {
+            let base_dir = "../test-workspaces";
+            let workspace = guard
+                .create_workspace(&generate_test_workspace_name("rename"), base_dir, "test/")
+                .unwrap();
+            guard.add_workspace(workspace.name.clone());
+            let other = guard
+                .create_workspace(
+                    &generate_test_workspace_name("rename-other"),
+                    base_dir,
+                    "test/",
+                )
+                .unwrap();
+            guard.add_workspace(other.name.clone());
+
+            let new_task_name = generate_test_workspace_name("renamed");
+            let renamed = guard
+                .manager
+                .rename_workspace(&workspace, &new_task_name)
+                .unwrap();
+            guard.add_workspace(renamed.name.clone());
+
+            // タイムスタンプは維持され、ブランチとディレクトリが新しい名前になる
+            assert_eq!(renamed.name[..15], workspace.name[..15]);
+            assert_eq!(renamed.task_name(), new_task_name);
+            assert_eq!(renamed.branch, format!("test/{}", renamed.name));
+            assert!(renamed.path.ends_with(&renamed.name));
+            assert!(!Path::new(&workspace.path).exists());
+            assert!(guard
+                .manager
+                .repo
+                .find_branch(&workspace.branch, BranchType::Local)
+                .is_err());
+            // worktree作成時に自動生成されたブランチも残らない
+            assert!(guard
+                .manager
+                .repo
+                .find_branch(&workspace.name, BranchType::Local)
+                .is_err());
+
+            // gitからも新しい場所・ブランチとして認識される
+            let listed = guard
+                .manager
+                .list_workspaces()
+                .unwrap()
+                .into_iter()
+                .find(|ws| ws.name == renamed.name)
+                .unwrap();
+            assert_eq!(listed.branch, renamed.branch);
+            assert_eq!(
+                fs::canonicalize(&listed.path).unwrap(),
+                fs::canonicalize(&renamed.path).unwrap()
+            );
+            let worktree_repo = Repository::open(&renamed.path).unwrap();
+            assert_eq!(
+                worktree_repo.head().unwrap().shorthand(),
+                Some(renamed.branch.as_str())
+            );
+
+            // メタデータも引き継がれる
+            assert!(guard
+                .manager
+                .workspace_metadata(&renamed.name)
+                .base_commit
+                .is_some());
+            assert!(guard
+                .manager
+                .workspace_metadata(&workspace.name)
+                .base_commit
+                .is_none());
+
+            // 既存のワークスペースと同じ名前や不正な名前は拒否される
+            assert!(guard
+                .manager
+                .rename_workspace(&listed, other.task_name())
+                .is_err());
+            assert!(guard
+                .manager
+                .rename_workspace(&listed, "has space")
+                .is_err());
+            assert!(Path::new(&listed.path).exists());
+        }
+    }
+
     #[test]
     fn test_remove_nonexistent_workspace() {
         if let Ok(manager) = WorkspaceManager::new() {
-            let result = manager.remove_workspace("nonexistent-workspace-12345");
+            let result = manager.remove_workspace("nonexistent-workspace-12345", false);
             assert!(result.is_err());
 
             if let Err(error_msg) = result {
@@ -1032,7 +2313,7 @@ mod tests {
             ];
 
             // ファイルをコピー
-            manager.copy_files(source_dir, &dest_dir, &copy_files);
+            manager.copy_files(source_dir, &dest_dir, &copy_files, &mut |_| {});
 
             // 検証
             assert!(dest_dir.join("test1.txt").exists());
@@ -1064,7 +2345,7 @@ mod tests {
             let copy_files = vec!["config/nested/deep/file.yml".to_string()];
 
             // ファイルをコピー
-            manager.copy_files(source_dir, &dest_dir, &copy_files);
+            manager.copy_files(source_dir, &dest_dir, &copy_files, &mut |_| {});
 
             // 検証
             let dest_file = dest_dir.join("config/nested/deep/file.yml");
@@ -1083,7 +2364,7 @@ mod tests {
 
             // 空のリストでコピー処理を実行
             let copy_files: Vec<String> = vec![];
-            manager.copy_files(source_dir, &dest_dir, &copy_files);
+            manager.copy_files(source_dir, &dest_dir, &copy_files, &mut |_| {});
 
             // エラーが発生しないことを確認（パニックしない）
             assert!(dest_dir.exists());
@@ -1106,7 +2387,7 @@ mod tests {
             ];
 
             // コマンドを実行
-            manager.execute_pre_commands(&workspace_dir, &pre_commands);
+            manager.execute_pre_commands(&workspace_dir, &pre_commands, &mut |_| {});
 
             // 実行結果の確認
             let output_file = workspace_dir.join("test_output.txt");
@@ -1132,7 +2413,7 @@ mod tests {
             ];
 
             // コマンドを実行（失敗しても処理が継続することを確認）
-            manager.execute_pre_commands(&workspace_dir, &pre_commands);
+            manager.execute_pre_commands(&workspace_dir, &pre_commands, &mut |_| {});
 
             // 成功したコマンドの結果は残っている
             assert!(workspace_dir.join("success1.txt").exists());
@@ -1140,6 +2421,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_execute_pre_commands_setup_log() {
+        if let Ok(manager) = WorkspaceManager::new() {
+            let temp_dir = TempDir::new().unwrap();
+
+            let pre_commands = vec![
+                "echo 'installing'".to_string(),
+                "echo 'oops' >&2; exit 3".to_string(),
+            ];
+            let log = manager.execute_pre_commands(temp_dir.path(), &pre_commands, &mut |_| {});
+
+            // コマンド、標準出力、標準エラー出力、終了コードが記録される
+            assert_eq!(
+                log,
+                "$ echo 'installing'\ninstalling\n$ echo 'oops' >&2; exit 3\noops\n[exit code: 3]\n"
+            );
+
+            // シグナルで終了した場合はシグナル番号も記録される
+            #[cfg(unix)]
+            {
+                let pre_commands = vec!["kill -9 $$".to_string()];
+                let log = manager.execute_pre_commands(temp_dir.path(), &pre_commands, &mut |_| {});
+                assert_eq!(log, "$ kill -9 $$\n[exit code: 137, killed by signal 9]\n");
+            }
+        }
+    }
+
     #[test]
     fn test_execute_pre_commands_empty_list() {
         if let Ok(manager) = WorkspaceManager::new() {
@@ -1149,7 +2457,7 @@ mod tests {
 
             // 空のコマンドリストで実行
             let pre_commands: Vec<String> = vec![];
-            manager.execute_pre_commands(&workspace_dir, &pre_commands);
+            manager.execute_pre_commands(&workspace_dir, &pre_commands, &mut |_| {});
 
             // エラーが発生しないことを確認（パニックしない）
             assert!(workspace_dir.exists());
@@ -1166,7 +2474,7 @@ mod tests {
             // カレントディレクトリを確認するコマンド
             let pre_commands = vec!["pwd > current_dir.txt".to_string()];
 
-            manager.execute_pre_commands(&workspace_dir, &pre_commands);
+            manager.execute_pre_commands(&workspace_dir, &pre_commands, &mut |_| {});
 
             // 作業ディレクトリが正しく設定されていることを確認
             let output_file = workspace_dir.join("current_dir.txt");
@@ -1188,6 +2496,7 @@ mod tests {
                 name: "test-workspace".to_string(),
                 path: ".".to_string(), // 現在のディレクトリ（存在することが確実）
                 branch: "test/branch".to_string(),
+                locked: None,
             };
 
             // 詳細情報を取得
@@ -1220,6 +2529,7 @@ mod tests {
                 name: "nonexistent-workspace".to_string(),
                 path: "/path/that/does/not/exist".to_string(),
                 branch: "test/branch".to_string(),
+                locked: None,
             };
 
             // 詳細情報を取得
@@ -1247,4 +2557,401 @@ mod tests {
                 .contains(&"ワークスペースが存在しません".to_string()));
         }
     }
+
+    // テスト用のベアリポジトリを作成（初期コミット付き）
+    fn init_bare_repo_with_commit(path: &Path) -> Repository {
+        let repo = Repository::init_bare(path).unwrap();
+        {
+            let sig = git2::Signature::now("test", "test@example.com").unwrap();
+            let tree_id = repo.treebuilder(None).unwrap().write().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+                .unwrap();
+        }
+        repo
+    }
+
+    #[test]
+    fn test_bare_repository_detection() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare_path = temp_dir.path().join("project.git");
+        let repo = init_bare_repo_with_commit(&bare_path);
+
+        // ベアリポジトリ自体を開いた場合
+        let manager = WorkspaceManager::open(&bare_path).unwrap();
+        assert!(manager.is_bare);
+
+        // ベアリポジトリのworktree内から開いた場合
+        let main_path = temp_dir.path().join("main");
+        repo.worktree("main", &main_path, None).unwrap();
+        let manager = WorkspaceManager::open(&main_path).unwrap();
+        assert!(manager.is_bare);
+
+        // 通常のリポジトリはベアではない
+        let manager = WorkspaceManager::new().unwrap();
+        assert!(!manager.is_bare);
+    }
+
+    #[test]
+    fn test_list_workspaces_bare_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare_path = temp_dir.path().join("project.git");
+        let repo = init_bare_repo_with_commit(&bare_path);
+        repo.worktree("main", &temp_dir.path().join("main"), None)
+            .unwrap();
+        repo.worktree("feature", &temp_dir.path().join("feature"), None)
+            .unwrap();
+
+        // ベアリポジトリでは "main" を含むすべてのworktreeがワークスペースとなる
+        let manager = WorkspaceManager::open(&bare_path).unwrap();
+        let mut names: Vec<String> = manager
+            .list_workspaces()
+            .unwrap()
+            .into_iter()
+            .map(|ws| ws.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["feature", "main"]);
+    }
+
+    // HEADに空のコミットを追加
+    fn commit_on_head(repo: &Repository, message: &str) -> Oid {
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let tree = parent.tree().unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &[&parent])
+            .unwrap()
+    }
+
+    #[test]
+    fn test_get_branch_tracking() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare_path = temp_dir.path().join("project.git");
+        let repo = init_bare_repo_with_commit(&bare_path);
+        let base = repo.head().unwrap().shorthand().unwrap().to_string();
+        let initial_oid = repo.head().unwrap().target().unwrap();
+        let feature_path = temp_dir.path().join("feature");
+        repo.worktree("feature", &feature_path, None).unwrap();
+
+        let manager = WorkspaceManager::open(&bare_path).unwrap();
+        manager
+            .update_state(|state| {
+                state.workspaces.insert(
+                    "feature".to_string(),
+                    WorkspaceMetadata {
+                        base_ref: Some(base.clone()),
+                        base_commit: Some(initial_oid.to_string()),
+                    },
+                );
+            })
+            .unwrap();
+        let workspace = WorkspaceInfo {
+            name: "feature".to_string(),
+            path: feature_path.to_str().unwrap().to_string(),
+            branch: "feature".to_string(),
+            locked: None,
+        };
+
+        // 作成直後: ベースと同じ位置で、マージ済み扱いにはしない
+        let tracking = manager.get_branch_tracking(&workspace);
+        assert_eq!(tracking.base.as_deref(), Some(base.as_str()));
+        assert_eq!(tracking.base_counts, Some(AheadBehind::default()));
+        assert!(!tracking.merged);
+        assert!(tracking.upstream.is_none());
+        assert!(!tracking.pushed);
+
+        // ワークスペースとベースの両方でコミット
+        let feature_repo = Repository::open(&feature_path).unwrap();
+        let feature_oid = commit_on_head(&feature_repo, "feature work");
+        commit_on_head(&repo, "base work");
+        let tracking = manager.get_branch_tracking(&workspace);
+        assert_eq!(
+            tracking.base_counts,
+            Some(AheadBehind {
+                ahead: 1,
+                behind: 1
+            })
+        );
+        assert!(!tracking.merged);
+
+        // upstreamを設定（push済み）
+        repo.remote("origin", "https://example.com/project.git")
+            .unwrap();
+        repo.reference("refs/remotes/origin/feature", feature_oid, true, "push")
+            .unwrap();
+        repo.find_branch("feature", BranchType::Local)
+            .unwrap()
+            .set_upstream(Some("origin/feature"))
+            .unwrap();
+        let tracking = manager.get_branch_tracking(&workspace);
+        assert_eq!(tracking.upstream.as_deref(), Some("origin/feature"));
+        assert_eq!(tracking.upstream_counts, Some(AheadBehind::default()));
+        assert!(tracking.pushed);
+
+        // 未pushのコミットがある場合
+        let feature_oid = commit_on_head(&feature_repo, "more work");
+        let tracking = manager.get_branch_tracking(&workspace);
+        assert_eq!(
+            tracking.upstream_counts,
+            Some(AheadBehind {
+                ahead: 1,
+                behind: 0
+            })
+        );
+        assert!(!tracking.pushed);
+
+        // ベースにfast-forwardでマージされた場合
+        repo.reference(&format!("refs/heads/{base}"), feature_oid, true, "merge")
+            .unwrap();
+        let tracking = manager.get_branch_tracking(&workspace);
+        assert_eq!(tracking.base_counts, Some(AheadBehind::default()));
+        assert!(tracking.merged);
+    }
+
+    #[test]
+    fn test_workspace_status_counts() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let root = temp_dir.path();
+
+        // 初期コミット
+        fs::write(root.join("a.txt"), "a\n").unwrap();
+        fs::write(root.join("b.txt"), "b\n").unwrap();
+        fs::write(root.join("c.txt"), "c\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "initial", &tree, &[])
+            .unwrap();
+
+        let status = WorkspaceStatus::from_repo(&repo).unwrap();
+        assert!(status.is_clean());
+        assert_eq!(status.label(), "Clean");
+
+        // ステージ済みの新規ファイル、未ステージの変更、未追跡ファイル、リネーム
+        fs::write(root.join("new.txt"), "new\n").unwrap();
+        index.add_path(Path::new("new.txt")).unwrap();
+        fs::write(root.join("a.txt"), "changed\n").unwrap();
+        fs::write(root.join("untracked.txt"), "?\n").unwrap();
+        fs::rename(root.join("b.txt"), root.join("renamed.txt")).unwrap();
+        index.remove_path(Path::new("b.txt")).unwrap();
+        index.add_path(Path::new("renamed.txt")).unwrap();
+        index.write().unwrap();
+
+        let status = WorkspaceStatus::from_repo(&repo).unwrap();
+        assert_eq!(status.staged, 2); // new.txt, b.txt -> renamed.txt
+        assert_eq!(status.unstaged, 1); // a.txt
+        assert_eq!(status.untracked, 1); // untracked.txt
+        assert_eq!(status.renamed, 1);
+        assert_eq!(status.conflicted, 0);
+        assert_eq!(status.label(), "Modified");
+
+        let mut files = changed_files(&repo).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                " M a.txt",
+                "?? untracked.txt",
+                "A  new.txt",
+                "R  b.txt -> renamed.txt",
+            ]
+        );
+
+        // コンフリクトとマージ中の状態
+        let conflicts: Vec<git2::IndexEntry> = (1..=3u16)
+            .map(|stage| {
+                // ステージ1-3のエントリはコンフリクトを表す
+                let mut entry = index.get_path(Path::new("c.txt"), 0).unwrap();
+                entry.flags = (entry.flags & !0x3000) | (stage << 12);
+                entry
+            })
+            .collect();
+        index.remove_path(Path::new("c.txt")).unwrap();
+        for entry in &conflicts {
+            index.add(entry).unwrap();
+        }
+        index.write().unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        fs::write(repo.path().join("MERGE_HEAD"), format!("{head}\n")).unwrap();
+
+        let status = WorkspaceStatus::from_repo(&repo).unwrap();
+        assert_eq!(status.conflicted, 1);
+        assert_eq!(status.operation, Some("merge"));
+        assert_eq!(status.label(), "merge in progress");
+        assert!(!status.is_clean());
+    }
+
+    #[test]
+    fn test_resolve_copy_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare_path = temp_dir.path().join("project.git");
+        let repo = init_bare_repo_with_commit(&bare_path);
+        let template_path = temp_dir.path().join("template");
+        repo.worktree("template", &template_path, None).unwrap();
+
+        let manager = WorkspaceManager::open(&bare_path).unwrap();
+
+        // ベアリポジトリ自体には作業ツリーがないためコピー元なし
+        assert!(manager.resolve_copy_source(None).is_none());
+
+        // worktree名で指定
+        let source = manager.resolve_copy_source(Some("template")).unwrap();
+        assert!(source.ends_with("template"));
+
+        // ディレクトリで指定
+        let dir = temp_dir.path().to_str().unwrap();
+        assert_eq!(
+            manager.resolve_copy_source(Some(dir)),
+            Some(temp_dir.path().to_path_buf())
+        );
+
+        // 存在しない指定
+        assert!(manager
+            .resolve_copy_source(Some("/path/that/does/not/exist"))
+            .is_none());
+
+        // 通常のリポジトリではカレントディレクトリ
+        let manager = WorkspaceManager::new().unwrap();
+        assert_eq!(manager.resolve_copy_source(None), Some(PathBuf::from(".")));
+    }
+
+    fn test_workspace_info(name: &str, branch: &str) -> WorkspaceInfo {
+        WorkspaceInfo {
+            name: name.to_string(),
+            path: format!("/path/{name}"),
+            branch: branch.to_string(),
+            locked: None,
+        }
+    }
+
+    #[test]
+    fn test_workspace_info_task_name() {
+        let info = test_workspace_info("20250625-120000-feature-auth", "work/x");
+        assert_eq!(info.task_name(), "feature-auth");
+
+        // タイムスタンプ形式でない名前はそのまま
+        let info = test_workspace_info("feature", "feature");
+        assert_eq!(info.task_name(), "feature");
+    }
+
+    #[test]
+    fn test_workspace_info_created_at() {
+        let info = test_workspace_info("20250625-120000-feature-auth", "work/x");
+        assert_eq!(
+            info.created_at().unwrap().to_string(),
+            "2025-06-25 12:00:00"
+        );
+
+        // タイムスタンプ形式でない名前は作成日時なし
+        assert!(test_workspace_info("feature", "feature")
+            .created_at()
+            .is_none());
+        assert!(test_workspace_info("20251399-999999-x", "x")
+            .created_at()
+            .is_none());
+    }
+
+    #[test]
+    fn test_match_workspaces() {
+        let workspaces = vec![
+            test_workspace_info(
+                "20250625-120000-feature-auth",
+                "work/20250625-120000-feature-auth",
+            ),
+            test_workspace_info(
+                "20250625-130000-bugfix-login",
+                "work/20250625-130000-bugfix-login",
+            ),
+            test_workspace_info(
+                "20250626-090000-feature-api",
+                "work/20250626-090000-feature-api",
+            ),
+        ];
+
+        // 名前の完全一致
+        let result = match_workspaces(workspaces.clone(), "20250625-130000-bugfix-login");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "20250625-130000-bugfix-login");
+
+        // ブランチの完全一致
+        let result = match_workspaces(workspaces.clone(), "work/20250626-090000-feature-api");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "20250626-090000-feature-api");
+
+        // タスク名の完全一致
+        let result = match_workspaces(workspaces.clone(), "feature-auth");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "20250625-120000-feature-auth");
+
+        // あいまい一致（一意）
+        let result = match_workspaces(workspaces.clone(), "login");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "20250625-130000-bugfix-login");
+
+        // あいまい一致（複数）
+        let result = match_workspaces(workspaces.clone(), "feature");
+        assert_eq!(result.len(), 2);
+
+        // 一致なし
+        assert!(match_workspaces(workspaces, "zzz").is_empty());
+    }
+
+    #[test]
+    fn test_filter_workspaces() {
+        let workspaces = vec![
+            test_workspace_info("20250625-120000-feature-auth", "work/feature-auth"),
+            test_workspace_info("20250625-130000-bugfix-login", "work/bugfix-login"),
+            test_workspace_info("20250626-090000-feature-api", "work/feature-api"),
+        ];
+
+        // 順序を保ったまま一致するものだけ残る
+        let result = filter_workspaces(workspaces.clone(), "feat");
+        let names: Vec<&str> = result.iter().map(|ws| ws.task_name()).collect();
+        assert_eq!(names, vec!["feature-auth", "feature-api"]);
+
+        // 空のパターンはすべてに一致
+        assert_eq!(filter_workspaces(workspaces.clone(), "").len(), 3);
+
+        assert!(filter_workspaces(workspaces, "zzz").is_empty());
+    }
+
+    #[test]
+    fn test_execute_in_workspace() {
+        if let Ok(manager) = WorkspaceManager::new() {
+            let temp_dir = TempDir::new().unwrap();
+            let info = WorkspaceInfo {
+                name: "20250625-120000-exec-test".to_string(),
+                path: temp_dir.path().to_str().unwrap().to_string(),
+                branch: "work/exec-test".to_string(),
+                locked: None,
+            };
+            let mut env = BTreeMap::new();
+            env.insert("CUSTOM_VAR".to_string(), "custom".to_string());
+
+            // シェル経由の単一コマンド：作業ディレクトリと環境変数を確認
+            let command =
+                vec!["echo \"$GITWS_TASK $CUSTOM_VAR\" > env.txt && pwd > pwd.txt".to_string()];
+            let code = manager.execute_in_workspace(&info, &command, &env).unwrap();
+            assert_eq!(code, 0);
+            let content = fs::read_to_string(temp_dir.path().join("env.txt")).unwrap();
+            assert_eq!(content.trim(), "exec-test custom");
+            let pwd = fs::read_to_string(temp_dir.path().join("pwd.txt")).unwrap();
+            assert!(pwd
+                .trim()
+                .ends_with(temp_dir.path().file_name().unwrap().to_str().unwrap()));
+
+            // 終了コードが伝播される
+            let command = vec!["sh".to_string(), "-c".to_string(), "exit 3".to_string()];
+            let code = manager.execute_in_workspace(&info, &command, &env).unwrap();
+            assert_eq!(code, 3);
+
+            // 空のコマンドはエラー
+            assert!(manager.execute_in_workspace(&info, &[], &env).is_err());
+        }
+    }
 }